@@ -0,0 +1,288 @@
+use crate::prelude::*;
+use crate::{CensusObject, PatternKind};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Digits used for each 5 cell column of a strip (`0` to `v`), and for the length of long runs of empty columns (`0` to `z`)
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The longest run of empty columns written with a single `y`
+const MAX_RUN: usize = 4 + DIGITS.len() - 1;
+
+/// Rows of cells encoded by each character
+const STRIP_HEIGHT: Index = 5;
+
+/// The name of a stable object in the apgcode format used by apgsearch and [Catagolue](https://catagolue.hatsya.com).
+///
+/// # Format
+/// ```txt
+/// xq4_153
+/// ```
+///
+/// - The prefix is `xs` and the population for still lifes, `xp` and the period for oscillators, or `xq` and
+///   the period for spaceships.
+/// - After the `_`, the cells are cut into strips 5 rows tall, separated by `z`.
+///   Each column of a strip is one character: `0`-`9` then `a`-`v` for the 32 ways to fill 5 cells, with the top cell as the lowest bit.
+///   Runs of empty columns are shortened to `w` (2), `x` (3), or `y` followed by a digit (`0` to `z` for 4 to 39), and empty columns at
+///   the end of a strip are left out.
+///
+/// Objects have many phases and orientations, but only one apgcode: the shortest (then alphabetically first) of them all.
+/// [`Apgcode::identify`] finds it by running the cells. [`Apgcode::parse`] and [`Display`](fmt::Display) don't,
+/// so any phase can be written and read back.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// # use simulations::{Apgcode, Pattern, PatternKind};
+/// let glider = Apgcode::identify(&Pattern::Glider.grid(), LifeRule::CONWAY).unwrap();
+/// assert_eq!(glider.to_string(), "xq4_153");
+///
+/// let code = Apgcode::parse("xp2_7").unwrap();
+/// assert_eq!((code.kind, code.period), (PatternKind::Oscillator, 2));
+/// assert_eq!((code.grid.width(), code.grid.height()), (1, 3));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Apgcode {
+    /// Either [`StillLife`](PatternKind::StillLife), [`Oscillator`](PatternKind::Oscillator), or [`Spaceship`](PatternKind::Spaceship)
+    pub kind: PatternKind,
+
+    /// Number of generations before the object repeats. Always `1` for still lifes.
+    pub period: u64,
+
+    /// The cells of the object, in the phase and orientation the code was written from
+    pub grid: BitGrid,
+}
+
+impl Apgcode {
+    /// Finds the canonical apgcode of the object made of the alive cells of `cells`, when run alone under `rule`.
+    ///
+    /// Returns `None` if the cells aren't a stable object. See [`CensusObject::identify`].
+    pub fn identify(cells: &BitGrid, rule: LifeRule) -> Option<Self> {
+        CensusObject::identify(cells, rule).map(|object| object.apgcode())
+    }
+
+    /// Parses an apgcode with an `xs`, `xp`, or `xq` prefix.
+    ///
+    /// Returns `None` if the code is malformed, or if a still life's population doesn't match its cells.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (prefix, cells) = text.trim().split_once('_')?;
+        let number: u64 = prefix.get(2..)?.parse().ok()?;
+        let kind = match prefix.get(..2)? {
+            "xs" => PatternKind::StillLife,
+            "xp" => PatternKind::Oscillator,
+            "xq" => PatternKind::Spaceship,
+            _ => return None,
+        };
+
+        let grid = decode(cells)?;
+        let period = match kind {
+            PatternKind::StillLife if number == grid.count_set() as u64 => 1,
+            PatternKind::StillLife => return None,
+            _ if number == 0 => return None,
+            _ => number,
+        };
+
+        Some(Self { kind, period, grid })
+    }
+}
+
+impl fmt::Display for Apgcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            PatternKind::StillLife => write!(f, "xs{}", self.grid.count_set())?,
+            PatternKind::Spaceship => write!(f, "xq{}", self.period)?,
+            _ => write!(f, "xp{}", self.period)?,
+        }
+        write!(f, "_{}", encode(&self.grid))
+    }
+}
+
+/// Encodes the first layer of `grid` as the part of an apgcode after the `_`
+pub(crate) fn encode(grid: &BitGrid) -> String {
+    let strips = (grid.height() + STRIP_HEIGHT - 1) / STRIP_HEIGHT;
+    let mut text = String::new();
+
+    for strip in 0..strips {
+        if strip > 0 {
+            text.push('z');
+        }
+
+        let columns: Vec<u8> = (0..grid.width())
+            .map(|x| {
+                (0..STRIP_HEIGHT)
+                    .map(|row| (row, STRIP_HEIGHT * strip + row))
+                    .filter(|&(_, y)| y < grid.height() && grid.get(x, y, 0))
+                    .fold(0, |column, (row, _)| column | (1 << row))
+            })
+            .collect();
+        let used = columns
+            .iter()
+            .rposition(|&column| column != 0)
+            .map_or(0, |i| i + 1);
+
+        let mut empty = 0;
+        for &column in &columns[..used] {
+            if column == 0 {
+                empty += 1;
+                continue;
+            }
+            push_empty(&mut text, empty);
+            empty = 0;
+            text.push(DIGITS[column as usize] as char);
+        }
+    }
+    text
+}
+
+/// Writes a run of `count` empty columns in as few characters as possible
+fn push_empty(text: &mut String, mut count: usize) {
+    while count > 0 {
+        match count {
+            1 => text.push('0'),
+            2 => text.push('w'),
+            3 => text.push('x'),
+            _ => {
+                let run = count.min(MAX_RUN);
+                text.push('y');
+                text.push(DIGITS[run - 4] as char);
+                count -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+/// Decodes the part of an apgcode after the `_` into a grid just tall and wide enough for its strips
+fn decode(text: &str) -> Option<BitGrid> {
+    let digit = |c: char| DIGITS.iter().position(|&d| d as char == c);
+
+    // Strips are split on `z` as it's read, since a `y` run can also be followed by a `z`
+    let mut strips: Vec<Vec<u8>> = alloc::vec![Vec::new()];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        let empty = match c {
+            'w' => 2,
+            'x' => 3,
+            'y' => 4 + digit(chars.next()?)?,
+            'z' => {
+                strips.push(Vec::new());
+                continue;
+            }
+            _ => {
+                strips.last_mut()?.push(digit(c)? as u8);
+                continue;
+            }
+        };
+        let columns = strips.last_mut()?;
+        columns.resize(columns.len() + empty, 0);
+    }
+
+    let width = strips.iter().map(Vec::len).max().unwrap_or(0);
+    let height = strips
+        .iter()
+        .enumerate()
+        .filter_map(|(i, columns)| {
+            let bits = columns.iter().fold(0, |all, &column| all | column);
+            (bits != 0).then(|| STRIP_HEIGHT as usize * i + 8 - bits.leading_zeros() as usize)
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut grid = BitGrid::new(width, height, 1);
+    for (i, columns) in strips.iter().enumerate() {
+        for (x, &column) in columns.iter().enumerate() {
+            for row in 0..STRIP_HEIGHT {
+                if column & (1 << row) != 0 {
+                    grid.set(x as Index, STRIP_HEIGHT * i as Index + row, 0, true);
+                }
+            }
+        }
+    }
+    Some(grid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pattern;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::block(Pattern::Block, "xs4_33")]
+    #[case::beehive(Pattern::Beehive, "xs6_696")]
+    #[case::loaf(Pattern::Loaf, "xs7_2596")]
+    #[case::boat(Pattern::Boat, "xs5_253")]
+    #[case::tub(Pattern::Tub, "xs4_252")]
+    #[case::ship(Pattern::Ship, "xs6_356")]
+    #[case::blinker(Pattern::Blinker, "xp2_7")]
+    #[case::toad(Pattern::Toad, "xp2_7e")]
+    #[case::beacon(Pattern::Beacon, "xp2_318c")]
+    #[case::pulsar(Pattern::Pulsar, "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401")]
+    #[case::pentadecathlon(Pattern::Pentadecathlon, "xp15_4r4z4r4")]
+    #[case::glider(Pattern::Glider, "xq4_153")]
+    #[case::lwss(Pattern::Lwss, "xq4_6frc")]
+    #[case::mwss(Pattern::Mwss, "xq4_27dee6")]
+    #[case::hwss(Pattern::Hwss, "xq4_27deee6")]
+    fn check_canonical_codes(#[case] pattern: Pattern, #[case] expected: &str) {
+        let code = Apgcode::identify(&pattern.grid(), LifeRule::CONWAY).unwrap();
+        assert_eq!(code.to_string(), expected);
+
+        // Decoding gives a phase of the same object
+        let parsed = Apgcode::parse(expected).unwrap();
+        assert_eq!(parsed, code);
+        assert_eq!(
+            CensusObject::identify(&parsed.grid, LifeRule::CONWAY),
+            CensusObject::from_pattern(pattern)
+        );
+    }
+
+    #[rstest]
+    #[case::single_empty_column("101", &["O.O"])]
+    #[case::empty_runs("1w1x1y01y51", &["O..O...O....O.........O"])]
+    #[case::two_strips("1z1", &["O", ".", ".", ".", ".", "O"])]
+    #[case::empty_strip("vzzv", &["O", "O", "O", "O", "O", ".", ".", ".", ".", ".", "O", "O", "O", "O", "O"])]
+    fn check_round_trip(#[case] text: &str, #[case] rows: &[&str]) {
+        let grid = decode(text).unwrap();
+        let decoded: Vec<String> = (0..grid.height())
+            .map(|y| {
+                (0..grid.width())
+                    .map(|x| if grid.get(x, y, 0) { 'O' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(decoded, rows);
+        assert_eq!(encode(&grid), text);
+    }
+
+    #[test]
+    fn check_long_empty_run() {
+        let mut grid = BitGrid::new(50, 1, 1);
+        grid.set(0, 0, 0, true);
+        grid.set(49, 0, 0, true);
+
+        // 48 empty columns is a run of 39 then a run of 9
+        assert_eq!(encode(&grid), "1yzy51");
+        assert_eq!(decode("1yzy51"), Some(grid));
+
+        // Runs of 36 to 38 use the digits after `v`
+        assert_eq!(decode("1yw1"), decode("1yv01"));
+        assert_eq!(decode("1yy1").map(|grid| grid.width()), Some(40));
+    }
+
+    #[rstest]
+    #[case::no_underscore("xs4")]
+    #[case::unknown_prefix("yl144_1_16_afb5f3db909e60548f086e22ee3353ac")]
+    #[case::wrong_population("xs5_33")]
+    #[case::zero_period("xp0_7")]
+    #[case::bad_digit("xs4_3!")]
+    #[case::unfinished_run("xp2_1y")]
+    fn check_parse_invalid(#[case] text: &str) {
+        assert_eq!(Apgcode::parse(text), None);
+    }
+}
@@ -0,0 +1,374 @@
+use crate::prelude::*;
+use crate::AutomatonRule;
+
+use alloc::string::String;
+use alloc::vec;
+
+/// A 1D cellular automaton with any number of colors and any radius, driven by an [`AutomatonRule`].
+///
+/// This works like [`Elementry`](crate::Elementry), except cells hold a `u8` state from `0` to `colors - 1` instead of a `bool`.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Automaton, AutomatonRule};
+/// let rule = AutomatonRule::totalistic("1635", 3, 1).unwrap();
+/// let mut sim = Automaton::new(rule, 9);
+/// sim.set(4, 1);
+///
+/// sim.step();
+/// assert_eq!(sim.to_ascii(), "...222...");
+/// sim.step();
+/// assert_eq!(sim.to_ascii(), "..12221..");
+/// ```
+#[derive(Clone)]
+pub struct Automaton<G: Grid<u8> = PackedGrid<u8>> {
+    /// Current state of the simulation
+    cells: G,
+
+    /// Scratch copy of cells used when stepping the simulation
+    scratch: G,
+
+    rule: AutomatonRule,
+
+    /// How cells beyond the ends of `cells` are treated
+    boundary: Boundary,
+
+    /// Number of steps taken since creation or the last reset
+    generation: u64,
+}
+
+/// Basic Usage
+impl Automaton<PackedGrid<u8>> {
+    /// Creates a new simulation with the given rule and width where all cells are initially `0`.
+    ///
+    /// Cells are packed into the fewest bits that can hold every color of `rule`.
+    pub fn new(rule: AutomatonRule, width: usize) -> Self {
+        let bits = match rule.colors() {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
+        Self::new_with_cells(rule, PackedGrid::new_with_bits(width, 1, 1, bits))
+    }
+
+    /// Sets every cell to a random color using the provided rng.
+    pub fn clear_random(&mut self, rng: &mut impl rand::Rng) {
+        for x in 0..self.width() {
+            let state = rng.random_range(0..self.rule.colors());
+            self.set(x, state);
+        }
+    }
+}
+
+impl<G: Grid<u8> + Clone> Automaton<G> {
+    /// Creates a new simulation with the given rule and existing cells.
+    ///
+    /// Note: `cells` must be 1 dimensional (cells.height() == 1) or this method will panic.
+    pub fn new_with_cells(rule: AutomatonRule, cells: G) -> Self {
+        assert_eq!(
+            cells.height(),
+            1,
+            "Automaton only operates on a 1D grid of cells, but height == {}",
+            cells.height(),
+        );
+        assert_eq!(
+            cells.depth(),
+            1,
+            "Automaton only operates on a 1D grid of cells, but depth == {}",
+            cells.depth()
+        );
+
+        let scratch = cells.clone();
+        let boundary = cells.boundary();
+        Self {
+            cells,
+            scratch,
+            rule,
+            boundary,
+            generation: 0,
+        }
+    }
+}
+
+impl<G: Grid<u8>> Automaton<G> {
+    /// The width of the simulation
+    pub fn width(&self) -> Index {
+        self.cells.width()
+    }
+
+    /// The state of every cell, from left to right
+    pub fn cells(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.width()).map(|x| self.get(x))
+    }
+
+    /// Borrow the underlying grid of cells
+    pub fn grid(&self) -> &G {
+        &self.cells
+    }
+
+    /// How many times the simulation has stepped since it was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn rule(&self) -> &AutomatonRule {
+        &self.rule
+    }
+
+    /// How cells beyond the ends of the simulation are treated
+    ///
+    /// This defaults to the boundary of the grid the simulation was created with.
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Sets how cells beyond the ends of the simulation are treated
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    /// The state of the cell at `x`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    pub fn get(&self, x: Index) -> u8 {
+        self.cells.get_with_boundary(x, 0, 0, self.boundary)
+    }
+
+    /// Sets the cell at `x` to `state`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells outside of a bounded simulation are ignored.
+    ///
+    /// # Panics
+    /// If `state` isn't one of the rule's [`colors()`](AutomatonRule::colors).
+    #[track_caller]
+    pub fn set(&mut self, x: Index, state: u8) {
+        assert!(
+            state < self.rule.colors(),
+            "A {} color rule has no state {state}",
+            self.rule.colors()
+        );
+        self.cells.set_with_boundary(x, 0, 0, state, self.boundary);
+    }
+
+    /// Number of cells in `state`
+    pub fn count(&self, state: u8) -> usize {
+        self.cells().filter(|&s| s == state).count()
+    }
+
+    /// Steps the simulation once, returning the number of cells updated
+    ///
+    /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
+    pub fn step(&mut self) -> u32 {
+        let mut count = 0;
+        let radius = self.rule.radius() as Index;
+        let mut neighborhood = vec![0; self.rule.width()];
+
+        for x in 0..self.width() {
+            for (cell, dx) in neighborhood.iter_mut().zip(-radius..=radius) {
+                *cell = self.get(x + dx);
+            }
+
+            let was = neighborhood[radius as usize];
+            let is = self.rule.next_state(&neighborhood);
+            self.scratch.set(x, 0, 0, is);
+
+            count += (was != is) as u32;
+        }
+
+        core::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
+
+        count
+    }
+
+    /// Sets every cell to `0`
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Sets every cell to `0` and the [generation](Self::generation) back to `0`
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generation = 0;
+    }
+
+    /// Writes `0` as `.` and every other state as a digit, or a letter from `10` up
+    pub fn to_ascii(&self) -> String {
+        self.cells()
+            .map(|state| match state {
+                0 => '.',
+                _ => char::from_digit(state as u32, 36).unwrap_or('#'),
+            })
+            .collect()
+    }
+}
+
+/// Local typedef to simplify using image's image type. We won't be changing the backing store from a Vec.
+#[cfg(feature = "image")]
+type ImageBuffer<P> = image::ImageBuffer<P, alloc::vec::Vec<<P as image::Pixel>::Subpixel>>;
+
+#[cfg(feature = "image")]
+impl<G: Grid<u8>> Automaton<G> {
+    /// Convert the cells into a single row [`image::ImageBuffer`](image::ImageBuffer).
+    ///
+    /// # Coloring
+    /// Cells in state `n` are colored with `palette[n]`. States past the end of `palette` use its last color.
+    ///
+    /// # Panics
+    /// If `palette` is empty.
+    pub fn to_image<P>(&self, palette: &[P]) -> ImageBuffer<P>
+    where
+        P: image::Pixel,
+    {
+        assert!(!palette.is_empty(), "The palette needs at least one color");
+
+        ImageBuffer::<P>::from_fn(self.cells.width() as u32, 1, |x: u32, _y: u32| {
+            let state = self.get(x as Index) as usize;
+            palette[state.min(palette.len() - 1)]
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CodeKind, Elementry};
+
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, SeedableRng};
+    use rstest::*;
+
+    #[rstest]
+    #[case::rule_30(30)]
+    #[case::rule_90(90)]
+    #[case::rule_110(110)]
+    #[case::rule_184(184)]
+    fn check_matches_elementry(#[case] rule: u8) {
+        let mut rng = SmallRng::seed_from_u64(rule as u64);
+        let mut elementry: Elementry = Elementry::new(rule, 70);
+        elementry.clear_random(&mut rng);
+
+        let mut sim = Automaton::new(AutomatonRule::from(rule), 70);
+        for (x, is_alive) in elementry.cells().enumerate() {
+            sim.set(x as Index, is_alive as u8);
+        }
+
+        for _ in 0..30 {
+            assert_eq!(sim.step(), elementry.step());
+            let expected: Vec<u8> = elementry.cells().map(u8::from).collect();
+            assert_eq!(sim.cells().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn check_wider_than_i16() {
+        let width = 40_000;
+        let rule = AutomatonRule::totalistic("1635", 3, 1).unwrap();
+        let mut sim = Automaton::new(rule, width);
+        assert_eq!(sim.width(), width as Index);
+
+        // A seed on the far end spreads across the wrapping edge to the start of the row
+        sim.set(width as Index - 1, 1);
+        assert_eq!(sim.step(), 3);
+        assert_eq!(sim.get(0), 2);
+        assert_eq!(sim.get(width as Index - 2), 2);
+        assert_eq!(sim.cells().count(), width);
+        assert_eq!(sim.to_ascii().len(), width);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_set_rejects_unknown_state() {
+        // 3 colors are packed into 2 bits, which could hold a 4th
+        let rule = AutomatonRule::totalistic("1635", 3, 1).unwrap();
+        let mut sim = Automaton::new(rule, 9);
+        sim.set(4, 3);
+    }
+
+    #[test]
+    fn check_totalistic_1635() {
+        let rule = AutomatonRule::totalistic("1635", 3, 1).unwrap();
+        let mut sim = Automaton::new(rule, 15);
+        sim.set(7, 1);
+
+        let mut rows = vec![sim.to_ascii()];
+        for _ in 0..4 {
+            sim.step();
+            rows.push(sim.to_ascii());
+        }
+
+        // Sums of 1, 4, and 6 become 2, and sums of 2 become 1
+        assert_eq!(
+            rows,
+            [
+                ".......1.......",
+                "......222......",
+                ".....12221.....",
+                "....2..2..2....",
+                "...111111111...",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_outer_totalistic_matches_general() {
+        // Rule 90 turns a cell on when exactly one of its neighbors is on: digits 2 and 3 of the code
+        let rule_90 = AutomatonRule::outer_totalistic("12", 2, 1).unwrap();
+        assert_eq!(rule_90.to_general(), AutomatonRule::elementry(90));
+        assert_eq!(rule_90.to_general().code(), "90");
+    }
+
+    #[test]
+    fn check_radius_2() {
+        // Every cell takes the state of the cell 2 to its right, which is the lowest digit of the neighborhood
+        let shift: u64 = (0..32).filter(|n| n % 2 == 1).map(|n| 1 << n).sum();
+        let rule = AutomatonRule::general(&shift.to_string(), 2, 2).unwrap();
+        assert_eq!(rule.width(), 5);
+
+        let mut sim = Automaton::new(rule, 12);
+        sim.set(6, 1);
+        sim.set(7, 1);
+        sim.step();
+        assert_eq!(sim.to_ascii(), "....11......");
+        sim.step();
+        assert_eq!(sim.to_ascii(), "..11........");
+    }
+
+    #[test]
+    fn check_big_codes_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let mut sim = Automaton::new(AutomatonRule::totalistic("0", 4, 2).unwrap(), 300);
+        sim.clear_random(&mut rng);
+        assert!(sim.cells().all(|state| state < 4));
+
+        // A general rule with 4 colors and radius 2 has 1024 digits in base 4, so codes can be over 600 digits in decimal
+        let code: String = core::iter::once('1')
+            .chain(
+                sim.cells()
+                    .map(|state| char::from_digit(state as u32 * 3, 10).unwrap()),
+            )
+            .collect();
+        let rule = AutomatonRule::general(&code, 4, 2).unwrap();
+        assert_eq!(rule.code(), code);
+
+        assert_eq!(AutomatonRule::general("000", 4, 2).unwrap().code(), "0");
+    }
+
+    #[rstest]
+    #[case::too_big(CodeKind::General, "256", 2, 1)]
+    #[case::too_big_totalistic(CodeKind::Totalistic, "2187", 3, 1)]
+    #[case::one_color(CodeKind::General, "0", 1, 1)]
+    #[case::not_a_number(CodeKind::General, "30a", 2, 1)]
+    #[case::empty(CodeKind::General, "", 2, 1)]
+    #[case::huge_table(CodeKind::General, "0", 4, 8)]
+    fn check_invalid_rules(
+        #[case] kind: CodeKind,
+        #[case] code: &str,
+        #[case] colors: u8,
+        #[case] radius: u8,
+    ) {
+        assert_eq!(AutomatonRule::new(kind, code, colors, radius), None);
+    }
+}
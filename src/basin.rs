@@ -0,0 +1,451 @@
+use crate::prelude::*;
+use crate::{Elementry, ElementryRule};
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// The state-transition graph of an elementary rule on a ring of cells: where every possible row goes in one step.
+///
+/// States are numbered by their cells, with the cell at `x` as bit `x`. Every state has exactly one successor,
+/// so following them always ends in a cycle. Each cycle is an [`Attractor`], and the states that lead into it are its basin.
+///
+/// The graph holds every state, so it is limited to rings of [`MAX_WIDTH`](Self::MAX_WIDTH) cells.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{ElementryRule, StateGraph};
+/// let graph = StateGraph::new(ElementryRule::new(90), 4);
+/// assert_eq!(graph.len(), 16);
+///
+/// // Rule 90 on 4 cells sends everything to all dead within 2 steps
+/// let basins = graph.basins();
+/// let [attractor] = basins.attractors() else { panic!() };
+/// assert_eq!(attractor.cycle, [0]);
+/// assert_eq!(attractor.basin_size, 16);
+/// assert_eq!(attractor.max_transient, 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateGraph {
+    rule: ElementryRule,
+    width: usize,
+
+    /// The state each state steps to
+    successors: Vec<u32>,
+}
+
+impl StateGraph {
+    /// The widest ring with a graph, which has `2^24` states
+    pub const MAX_WIDTH: usize = 24;
+
+    /// Steps every state of a ring `width` cells wide once with [`Elementry::step`].
+    ///
+    /// # Panics
+    /// If `width` is `0` or more than [`MAX_WIDTH`](Self::MAX_WIDTH).
+    #[track_caller]
+    pub fn new(rule: ElementryRule, width: usize) -> Self {
+        assert!(
+            (1..=Self::MAX_WIDTH).contains(&width),
+            "State graphs are only built for rings of 1 to {} cells, but width == {width}",
+            Self::MAX_WIDTH
+        );
+
+        let mut sim: Elementry = Elementry::new(rule.number(), width);
+        sim.set_boundary(Boundary::Wrap);
+
+        let mut row = [0];
+        let successors = (0..1_u64 << width)
+            .map(|state| {
+                sim.grid_mut().write_row(0, 0, &[state]);
+                sim.step();
+                sim.grid().read_row(0, 0, &mut row);
+                row[0] as u32
+            })
+            .collect();
+
+        Self {
+            rule,
+            width,
+            successors,
+        }
+    }
+
+    pub fn rule(&self) -> ElementryRule {
+        self.rule
+    }
+
+    /// Number of cells in the ring
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of states, which is `2^width`
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// Always `false`, since even a ring of one cell has two states
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    /// The state that `state` steps to
+    pub fn successor(&self, state: u32) -> u32 {
+        self.successors[state as usize]
+    }
+
+    /// How many states step to each state
+    pub fn in_degrees(&self) -> Vec<u32> {
+        let mut degrees = vec![0; self.len()];
+        for &next in &self.successors {
+            degrees[next as usize] += 1;
+        }
+        degrees
+    }
+
+    /// How many states have each in-degree: `histogram[n]` states have `n` predecessors.
+    ///
+    /// `histogram[0]` counts the Gardens of Eden. Every state has one successor, so the mean in-degree is always `1`.
+    pub fn in_degree_histogram(&self) -> Vec<u64> {
+        let mut histogram = Vec::new();
+        for degree in self.in_degrees() {
+            let degree = degree as usize;
+            if histogram.len() <= degree {
+                histogram.resize(degree + 1, 0);
+            }
+            histogram[degree] += 1;
+        }
+        histogram
+    }
+
+    /// Number of states that no state steps to
+    pub fn gardens_of_eden(&self) -> u64 {
+        self.in_degree_histogram().first().copied().unwrap_or(0)
+    }
+
+    /// Finds every attractor, and which one each state ends up in.
+    pub fn basins(&self) -> Basins {
+        const UNKNOWN: u32 = u32::MAX;
+        const ON_PATH: u32 = u32::MAX - 1;
+
+        let mut basin = vec![UNKNOWN; self.len()];
+        let mut transient = vec![0; self.len()];
+        let mut attractors: Vec<Attractor> = Vec::new();
+        let mut path = Vec::new();
+
+        for first in 0..self.len() as u32 {
+            if basin[first as usize] != UNKNOWN {
+                continue;
+            }
+
+            // Follow successors until reaching a state that is either known, or already on this path
+            path.clear();
+            let mut state = first;
+            while basin[state as usize] == UNKNOWN {
+                basin[state as usize] = ON_PATH;
+                transient[state as usize] = path.len() as u32;
+                path.push(state);
+                state = self.successor(state);
+            }
+
+            if basin[state as usize] == ON_PATH {
+                // A new cycle, from `state` to the end of the path
+                let id = attractors.len() as u32;
+                let start = transient[state as usize] as usize;
+                let mut cycle = path.split_off(start);
+                for &state in &cycle {
+                    basin[state as usize] = id;
+                    transient[state as usize] = 0;
+                }
+
+                let lowest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+                cycle.rotate_left(lowest);
+                attractors.push(Attractor {
+                    basin_size: cycle.len() as u64,
+                    cycle,
+                    max_transient: 0,
+                });
+            }
+
+            // The rest of the path leads into the basin of `state`
+            let id = basin[state as usize];
+            let mut distance = transient[state as usize];
+            for &state in path.iter().rev() {
+                distance += 1;
+                basin[state as usize] = id;
+                transient[state as usize] = distance;
+            }
+
+            let attractor = &mut attractors[id as usize];
+            attractor.basin_size += path.len() as u64;
+            attractor.max_transient = attractor.max_transient.max(distance);
+        }
+
+        Basins {
+            attractors,
+            basin,
+            transient,
+        }
+    }
+
+    /// Writes the graph in the DOT language, for drawing with Graphviz.
+    ///
+    /// Each basin is a cluster labelled with its period and size. States are labelled with their cells as `O` and `.`,
+    /// and states on a cycle are drawn with a double border.
+    pub fn to_dot(&self) -> String {
+        let basins = self.basins();
+        let mut dot = String::new();
+        self.write_dot(&mut dot, &basins)
+            .expect("Writing to a String never fails");
+        dot
+    }
+
+    fn write_dot(&self, w: &mut impl Write, basins: &Basins) -> core::fmt::Result {
+        writeln!(w, "digraph \"{}, width {}\" {{", self.rule, self.width)?;
+        writeln!(w, "  node [shape=box, fontname=monospace];")?;
+
+        let mut members = vec![Vec::new(); basins.attractors().len()];
+        for state in 0..self.len() as u32 {
+            members[basins.basin[state as usize] as usize].push(state);
+        }
+
+        for (id, (attractor, states)) in basins.attractors().iter().zip(members).enumerate() {
+            writeln!(w, "  subgraph cluster_{id} {{")?;
+            writeln!(
+                w,
+                "    label=\"period {}, basin {}\";",
+                attractor.period(),
+                attractor.basin_size
+            )?;
+
+            for state in states {
+                let label: String = (0..self.width)
+                    .map(|x| if state >> x & 1 != 0 { 'O' } else { '.' })
+                    .collect();
+                let border = if basins.transient(state) == 0 {
+                    ", peripheries=2"
+                } else {
+                    ""
+                };
+                writeln!(w, "    {state} [label=\"{label}\"{border}];")?;
+                writeln!(w, "    {state} -> {};", self.successor(state))?;
+            }
+            writeln!(w, "  }}")?;
+        }
+        writeln!(w, "}}")
+    }
+}
+
+/// A cycle of states that a ring falls into and never leaves, and the states that lead into it.
+///
+/// See [`StateGraph::basins`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attractor {
+    /// The states of the cycle in the order they step, starting with the lowest
+    pub cycle: Vec<u32>,
+
+    /// Number of states that end up in this cycle, including the cycle itself
+    pub basin_size: u64,
+
+    /// The most steps any state of the basin takes to reach the cycle
+    pub max_transient: u32,
+}
+
+impl Attractor {
+    /// Number of steps around the cycle
+    pub fn period(&self) -> usize {
+        self.cycle.len()
+    }
+}
+
+/// Every [`Attractor`] of a [`StateGraph`], and where each state ends up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Basins {
+    /// Ordered by the lowest state in their basin
+    attractors: Vec<Attractor>,
+
+    /// The index into `attractors` of each state's attractor
+    basin: Vec<u32>,
+
+    /// Steps from each state to its attractor's cycle
+    transient: Vec<u32>,
+}
+
+impl Basins {
+    /// Every attractor, ordered by the lowest state in its basin
+    pub fn attractors(&self) -> &[Attractor] {
+        &self.attractors
+    }
+
+    /// The attractor that `state` ends up in
+    pub fn attractor_of(&self, state: u32) -> &Attractor {
+        &self.attractors[self.basin[state as usize] as usize]
+    }
+
+    /// Number of steps `state` takes to reach a cycle, which is `0` for states on one
+    pub fn transient(&self, state: u32) -> u32 {
+        self.transient[state as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    /// One step of `rule` on a ring `width` cells wide, where bit `x` is the cell at `x`
+    fn step(rule: ElementryRule, width: usize, state: u32) -> u32 {
+        let cell = |x: usize| state >> (x % width) & 1 != 0;
+        (0..width)
+            .filter(|&x| rule.next_state(cell(x + width - 1), cell(x), cell(x + 1)))
+            .fold(0, |next, x| next | 1 << x)
+    }
+
+    #[rstest]
+    #[case::rule_30(30, 7)]
+    #[case::rule_110(110, 9)]
+    #[case::rule_184(184, 10)]
+    #[case::one_cell(90, 1)]
+    #[case::two_cells(30, 2)]
+    fn check_matches_ring_step(#[case] rule: u8, #[case] width: usize) {
+        let rule = ElementryRule::new(rule);
+        let graph = StateGraph::new(rule, width);
+
+        for state in 0..graph.len() as u32 {
+            assert_eq!(graph.successor(state), step(rule, width, state));
+        }
+    }
+
+    #[rstest]
+    #[case::rule_30(30, 8)]
+    #[case::rule_110(110, 10)]
+    #[case::rule_90(90, 6)]
+    #[case::rule_184(184, 8)]
+    #[case::rule_204(204, 5)]
+    fn check_basins_are_consistent(#[case] rule: u8, #[case] width: usize) {
+        let graph = StateGraph::new(ElementryRule::new(rule), width);
+        let basins = graph.basins();
+
+        // Every state belongs to exactly one basin
+        let total: u64 = basins.attractors().iter().map(|a| a.basin_size).sum();
+        assert_eq!(total, graph.len() as u64);
+
+        for attractor in basins.attractors() {
+            let cycle = &attractor.cycle;
+            assert_eq!(cycle[0], *cycle.iter().min().unwrap());
+            for (i, &state) in cycle.iter().enumerate() {
+                assert_eq!(graph.successor(state), cycle[(i + 1) % cycle.len()]);
+                assert_eq!(basins.transient(state), 0);
+            }
+        }
+
+        for state in 0..graph.len() as u32 {
+            // Stepping `transient` times lands on the cycle of the same attractor
+            let mut on_cycle = state;
+            for _ in 0..basins.transient(state) {
+                on_cycle = graph.successor(on_cycle);
+            }
+            let attractor = basins.attractor_of(state);
+            assert!(attractor.cycle.contains(&on_cycle));
+            assert!(basins.transient(state) <= attractor.max_transient);
+
+            if basins.transient(state) > 0 {
+                assert_eq!(
+                    basins.transient(graph.successor(state)),
+                    basins.transient(state) - 1
+                );
+            }
+        }
+
+        let histogram = graph.in_degree_histogram();
+        assert_eq!(histogram.iter().sum::<u64>(), graph.len() as u64);
+        let edges: u64 = histogram
+            .iter()
+            .enumerate()
+            .map(|(n, &count)| n as u64 * count)
+            .sum();
+        assert_eq!(edges, graph.len() as u64);
+    }
+
+    #[test]
+    fn check_identity_and_shift() {
+        // Rule 204 leaves every state alone, so each one is its own attractor
+        let graph = StateGraph::new(ElementryRule::new(204), 4);
+        let basins = graph.basins();
+        assert_eq!(basins.attractors().len(), 16);
+        assert!(basins
+            .attractors()
+            .iter()
+            .all(|a| a.period() == 1 && a.basin_size == 1));
+        assert_eq!(graph.in_degree_histogram(), [0, 16]);
+
+        // Rule 170 shifts every cell left, so each state cycles through its rotations
+        let graph = StateGraph::new(ElementryRule::new(170), 4);
+        let periods: Vec<usize> = graph
+            .basins()
+            .attractors()
+            .iter()
+            .map(Attractor::period)
+            .collect();
+        assert_eq!(periods, [1, 4, 4, 2, 4, 1]);
+        assert_eq!(graph.gardens_of_eden(), 0);
+    }
+
+    #[test]
+    fn check_rule_30_ring_of_5() {
+        let graph = StateGraph::new(ElementryRule::new(30), 5);
+        let basins = graph.basins();
+
+        // Gardens of Eden agree with the preimage search
+        let gardens = (0..32)
+            .filter(|&state| {
+                let row: Vec<bool> = (0..5).map(|x| state >> x & 1 != 0).collect();
+                ElementryRule::new(30).is_garden_of_eden(&row)
+            })
+            .count();
+        assert_eq!(graph.gardens_of_eden(), gardens as u64);
+
+        // All dead is a still life, and all alive steps straight into it
+        assert_eq!(basins.attractors()[0].cycle, [0]);
+        assert_eq!(basins.attractor_of(0).basin_size, 2);
+        assert_eq!(basins.transient(31), 1);
+
+        // Everything else falls into a single cycle through the rotations of `OOO..`
+        assert_eq!(
+            basins.attractors()[1],
+            Attractor {
+                cycle: vec![0b00111, 0b11001, 0b01110, 0b10011, 0b11100],
+                basin_size: 30,
+                max_transient: 5,
+            }
+        );
+        assert_eq!(graph.in_degree_histogram(), [6, 20, 6]);
+    }
+
+    #[test]
+    fn check_to_dot() {
+        let dot = StateGraph::new(ElementryRule::new(90), 2).to_dot();
+        assert_eq!(
+            dot,
+            indoc::indoc! {r#"
+                digraph "Rule 90, width 2" {
+                  node [shape=box, fontname=monospace];
+                  subgraph cluster_0 {
+                    label="period 1, basin 4";
+                    0 [label="..", peripheries=2];
+                    0 -> 0;
+                    1 [label="O."];
+                    1 -> 0;
+                    2 [label=".O"];
+                    2 -> 0;
+                    3 [label="OO"];
+                    3 -> 0;
+                  }
+                }
+            "#}
+        );
+    }
+}
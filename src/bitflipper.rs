@@ -1,10 +1,14 @@
 use crate::prelude::*;
 
+#[derive(Clone)]
 pub struct BitFlipper<G: Grid = crate::BitGrid> {
     pos: IVec3,
     dir: IVec3,
     dir_sign: IVec3,
     grid: G,
+
+    /// Number of flips taken, in either direction, since creation or the last reset
+    generation: u64,
 }
 
 impl<G: GridNew> BitFlipper<G> {
@@ -22,6 +26,7 @@ impl<G: Grid> BitFlipper<G> {
             dir,
             dir_sign: IVec3::one(),
             grid,
+            generation: 0,
         }
     }
 
@@ -52,10 +57,19 @@ impl<G: Grid> BitFlipper<G> {
         self.pos
     }
 
+    /// How many flips have been taken, forwards or backwards, since this sim was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     pub fn resize(&mut self, new_dims: IVec3) {
         self.grid.resize(new_dims);
     }
 
+    pub fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        self.grid.resize_with_anchor(new_dims, anchor);
+    }
+
     pub fn set_dir(&mut self, new_dir: IVec3) {
         // Sloppily approximate our current location in the new coordinate space
         let [x, y, z] = self.pos.as_array().map(|a| a.unsigned_abs().max(1) as u64);
@@ -75,6 +89,15 @@ impl<G: Grid> BitFlipper<G> {
         for _ in 0..dir.abs() {
             self.flip_and_advance_once(dir.signum());
         }
+        self.generation += dir.unsigned_abs() as u64;
+    }
+
+    /// Clears the grid and moves back to the origin, as if this sim was just created with the same direction
+    pub fn reset(&mut self) {
+        self.grid.clear();
+        self.pos = IVec3::zero();
+        self.dir_sign = IVec3::one();
+        self.generation = 0;
     }
 }
 
@@ -1,5 +1,7 @@
+use crate::grid::Resolved;
 use crate::prelude::*;
 
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -9,6 +11,7 @@ pub struct BitGrid {
     width: Index,
     height: Index,
     depth: Index,
+    boundary: Boundary,
 }
 
 impl core::fmt::Debug for BitGrid {
@@ -16,6 +19,8 @@ impl core::fmt::Debug for BitGrid {
         f.debug_struct("BitGrid")
             .field("width", &self.width)
             .field("height", &self.height)
+            .field("depth", &self.depth)
+            .field("boundary", &self.boundary)
             .field("set/unset", &(self.count_set(), self.count_unset()))
             .finish()
     }
@@ -30,9 +35,16 @@ impl BitGrid {
             width: width as Index,
             height: height as Index,
             depth: depth as Index,
+            boundary: Boundary::Wrap,
         }
     }
 
+    /// Builder-style version of [`set_boundary`](Self::set_boundary)
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
     pub fn new_with_fn<F>(width: usize, height: usize, depth: usize, mut func: F) -> Self
     where
         F: FnMut(Index, Index, Index) -> bool,
@@ -74,6 +86,18 @@ impl BitGrid {
         Some(grid)
     }
 
+    /// Parses a pattern in the RLE format used by Golly and the LifeWiki. See [`Rle`](crate::Rle) for details.
+    ///
+    /// The grid is sized to the `x` and `y` of the RLE header. Comments and the rule are discarded.
+    pub fn parse_rle(text: &str) -> Option<Self> {
+        crate::Rle::parse(text).map(|rle| rle.grid)
+    }
+
+    /// Writes the first layer (`z == 0`) of this grid in the RLE format, without a rule.
+    pub fn to_rle(&self) -> String {
+        crate::Rle::new(self.clone()).to_string()
+    }
+
     pub fn width(&self) -> Index {
         self.width
     }
@@ -90,6 +114,16 @@ impl BitGrid {
         IVec3::new(self.width, self.height, self.depth)
     }
 
+    /// How out of bounds coordinates are treated by [`get`](Self::get), [`set`](Self::set), and [`flip`](Self::flip).
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how out of bounds coordinates are treated. New grids [`Wrap`](Boundary::Wrap).
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
     pub fn is_empty(&self) -> bool {
         self.buf.iter().all(|&byte| byte == 0)
     }
@@ -108,16 +142,31 @@ impl BitGrid {
             .sum()
     }
 
+    /// Checks the cell at `(x, y, z)`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
     #[track_caller]
     pub fn get(&self, x: Index, y: Index, z: Index) -> bool {
+        let (x, y, z) = match self.boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => (pos.x, pos.y, pos.z),
+            Resolved::Constant(elem) => return elem,
+        };
         let (idx, bit) = self.idx(x, y, z);
         let mask = 1 << bit;
 
         (self.buf[idx] & mask) != 0
     }
 
+    /// Sets the cell at `(x, y, z)`, returning its previous state.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells that are outside of the grid entirely are ignored.
     #[track_caller]
     pub fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool {
+        let (x, y, z) = match self.boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => (pos.x, pos.y, pos.z),
+            Resolved::Constant(old) => return old,
+        };
         let (idx, bit) = self.idx(x, y, z);
         let mask = 1 << bit;
 
@@ -129,8 +178,15 @@ impl BitGrid {
         old
     }
 
+    /// Flips the cell at `(x, y, z)`, returning its previous state.
+    ///
+    /// Out of bounds access is resolved like [`set`](Self::set).
     #[track_caller]
     pub fn flip(&mut self, x: Index, y: Index, z: Index) -> bool {
+        let (x, y, z) = match self.boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => (pos.x, pos.y, pos.z),
+            Resolved::Constant(old) => return old,
+        };
         let (idx, bit) = self.idx(x, y, z);
         let mask = 1 << bit;
 
@@ -141,10 +197,32 @@ impl BitGrid {
         old
     }
 
+    /// Sets every cell to `set`
+    pub fn fill(&mut self, set: bool) {
+        if set {
+            self.buf.fill(0b1111_1111_u8);
+            self.clear_padding();
+        } else {
+            self.buf.fill(0b0000_0000_u8);
+        }
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         &self.buf
     }
 
+    /// Unsets the padding bits past the last cell, so they aren't counted as set cells.
+    ///
+    /// Call this after writing whole bytes through [`as_mut_bytes`](Self::as_mut_bytes).
+    pub(crate) fn clear_padding(&mut self) {
+        let used_bits = (self.width() * self.height() * self.depth()) as usize % 8;
+        if let Some(last) = self.buf.last_mut() {
+            if used_bits != 0 {
+                *last &= (1 << used_bits) - 1;
+            }
+        }
+    }
+
     pub fn as_mut_bytes(&mut self) -> &mut [u8] {
         &mut self.buf
     }
@@ -155,22 +233,174 @@ impl BitGrid {
         y = (y + self.height()) % self.height();
         z = (z + self.depth()) % self.depth();
 
-        let x = x as usize;
-        let y = y as usize;
-        let z = z as usize;
-
-        let i = x + y * (self.width() as usize) + z * ((self.width() * self.height()) as usize);
+        let i = crate::grid::flat_index(self.dims(), IVec3::new(x, y, z));
         let byte = i / 8;
         let bit = i % 8;
 
         (byte, bit as u8)
     }
 
+    /// Resizes the grid to `new_dims`, keeping existing cells at their current coordinates.
+    pub fn resize(&mut self, new_dims: IVec3) {
+        self.resize_with_anchor(new_dims, Anchor::TopLeft);
+    }
+
+    /// Resizes the grid to `new_dims`, placing existing cells according to `anchor`.
+    ///
+    /// Cells that land outside of the new dimensions are dropped, and newly exposed cells are unset.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::prelude::*;
+    /// let mut grid = BitGrid::new(2, 2, 1);
+    /// grid.set(0, 0, 0, true);
+    ///
+    /// // Grow by 2 in each direction, keeping the old cells in the middle
+    /// grid.resize_with_anchor(IVec3::new(4, 4, 1), Anchor::Center);
+    /// assert_eq!(grid.dims(), IVec3::new(4, 4, 1));
+    /// assert!(grid.get(1, 1, 0));
+    /// assert_eq!(grid.count_set(), 1);
+    /// ```
+    pub fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        let boundary = self.boundary;
+        crate::grid::resize_into(self, new_dims, anchor, |dims| {
+            Self::new(dims.x as usize, dims.y as usize, dims.z as usize).with_boundary(boundary)
+        });
+    }
+
+    /// Number of `u64` words needed to hold one row, as used by [`read_row`](Self::read_row)
+    pub(crate) fn words_per_row(&self) -> usize {
+        (self.width() as usize).div_ceil(64)
+    }
+
+    /// Copies the row at `(y, z)` into packed words: bit `i` of `words[k]` is the cell at `x = 64 * k + i`.
+    ///
+    /// Bits past the end of the row are unset. `(y, z)` must be in bounds.
+    pub(crate) fn read_row(&self, y: Index, z: Index, words: &mut [u64]) {
+        debug_assert_eq!(words.len(), self.words_per_row());
+        let width = self.width() as usize;
+        let start = self.row_start(y, z);
+
+        for (k, word) in words.iter_mut().enumerate() {
+            let len = usize::min(64, width - 64 * k);
+            *word = self.read_bits(start + 64 * k, len);
+        }
+    }
+
+    /// Copies packed words into the row at `(y, z)`. This is the inverse of [`read_row`](Self::read_row).
+    ///
+    /// Bits past the end of the row are ignored. `(y, z)` must be in bounds.
+    pub(crate) fn write_row(&mut self, y: Index, z: Index, words: &[u64]) {
+        debug_assert_eq!(words.len(), self.words_per_row());
+        let width = self.width() as usize;
+        let start = self.row_start(y, z);
+
+        for (k, &word) in words.iter().enumerate() {
+            let len = usize::min(64, width - 64 * k);
+            self.write_bits(start + 64 * k, len, word);
+        }
+    }
+
+    /// Like [`read_row`](Self::read_row), but accepts any `y` and resolves it with `boundary`.
+    ///
+    /// Returns the cells just past either end of the row, at `x = -1` and `x = width`.
+    pub(crate) fn read_row_with_boundary(
+        &self,
+        y: Index,
+        z: Index,
+        boundary: Boundary,
+        words: &mut [u64],
+    ) -> (bool, bool) {
+        let edges = (
+            self.get_with_boundary(-1, y, z, boundary),
+            self.get_with_boundary(self.width(), y, z, boundary),
+        );
+
+        if (0..self.height()).contains(&y) {
+            self.read_row(y, z, words);
+        } else {
+            // Rows outside of the grid are rare (two per step), so resolve them one cell at a time
+            words.fill(0);
+            for x in 0..self.width() {
+                if self.get_with_boundary(x, y, z, boundary) {
+                    words[x as usize / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Bit offset of the first cell in the row at `(y, z)`
+    fn row_start(&self, y: Index, z: Index) -> usize {
+        (y as usize) * (self.width() as usize)
+            + (z as usize) * ((self.width() * self.height()) as usize)
+    }
+
+    /// Reads `len <= 64` bits starting at bit `start` of the buffer
+    fn read_bits(&self, start: usize, len: usize) -> u64 {
+        let byte = start / 8;
+        let shift = start % 8;
+        if shift == 0 && len == 64 {
+            let bytes = self.buf[byte..byte + 8].try_into().unwrap();
+            return u64::from_le_bytes(bytes);
+        }
+        let byte_count = (shift + len).div_ceil(8);
+
+        let mut acc: u128 = 0;
+        for (i, &b) in self.buf[byte..byte + byte_count].iter().enumerate() {
+            acc |= (b as u128) << (8 * i);
+        }
+
+        let bits = (acc >> shift) as u64;
+        if len == 64 {
+            bits
+        } else {
+            bits & ((1 << len) - 1)
+        }
+    }
+
+    /// Writes the low `len <= 64` bits of `bits` starting at bit `start` of the buffer
+    fn write_bits(&mut self, start: usize, len: usize, bits: u64) {
+        let byte = start / 8;
+        let shift = start % 8;
+        if shift == 0 && len == 64 {
+            self.buf[byte..byte + 8].copy_from_slice(&bits.to_le_bytes());
+            return;
+        }
+        let byte_count = (shift + len).div_ceil(8);
+
+        let mask: u128 = if len == 64 {
+            u64::MAX as u128
+        } else {
+            (1 << len) - 1
+        };
+        let mask = mask << shift;
+        let bits = ((bits as u128) << shift) & mask;
+
+        for (i, b) in self.buf[byte..byte + byte_count].iter_mut().enumerate() {
+            let m = (mask >> (8 * i)) as u8;
+            *b = (*b & !m) | ((bits >> (8 * i)) as u8);
+        }
+    }
+
+    /// A copy of this grid with its cells turned or mirrored by `transform`.
+    ///
+    /// The width and height are swapped by transforms that swap the axes.
+    pub fn transformed(&self, transform: Transform) -> BitGrid {
+        let dims = transform.dims(self.dims());
+        let mut grid =
+            Self::new(dims.x as _, dims.y as _, dims.z as _).with_boundary(self.boundary);
+        grid.stamp_with(self, IVec3::zero(), transform, Blend::Or, Boundary::Dead);
+        grid
+    }
+
     pub fn diff_with(&self, other: &BitGrid) -> BitGrid {
         assert_eq!(self.width(), other.width());
         assert_eq!(self.height(), other.height());
 
-        let mut diff = Self::new(self.width() as _, self.height() as _, self.depth() as _);
+        let mut diff = Self::new(self.width() as _, self.height() as _, self.depth() as _)
+            .with_boundary(self.boundary);
         let bytes = diff.as_mut_bytes();
 
         for (i, (a, b)) in self.as_bytes().iter().zip(other.as_bytes()).enumerate() {
@@ -192,6 +422,18 @@ impl Grid for BitGrid {
         self.dims()
     }
 
+    fn boundary(&self) -> Boundary {
+        self.boundary()
+    }
+
+    fn as_bitgrid(&self) -> Option<&BitGrid> {
+        Some(self)
+    }
+
+    fn as_bitgrid_mut(&mut self) -> Option<&mut BitGrid> {
+        Some(self)
+    }
+
     #[track_caller]
     fn get(&self, x: Index, y: Index, z: Index) -> bool {
         self.get(x, y, z)
@@ -207,23 +449,12 @@ impl Grid for BitGrid {
         self.flip(x, y, z)
     }
 
-    fn resize(&mut self, new_dims: IVec3) {
-        let mut needs_resize = false;
-        for (o, n) in core::iter::zip(self.dims().as_array(), new_dims.as_array()) {
-            needs_resize |= n > o;
-        }
-
-        if needs_resize {
-            todo!();
-        }
+    fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        self.resize_with_anchor(new_dims, anchor)
     }
 
     fn fill(&mut self, set: bool) {
-        if set {
-            self.as_mut_bytes().fill(0b0000_0000_u8);
-        } else {
-            self.as_mut_bytes().fill(0b1111_1111_u8);
-        }
+        self.fill(set)
     }
 }
 
@@ -504,4 +735,158 @@ mod tests {
         assert_eq!(grid.as_bytes().len(), byte_len);
         assert_eq!(grid.as_bytes(), vec![0b1111_1111; byte_len]);
     }
+
+    #[rstest]
+    #[case::grow_top_left(8, Anchor::TopLeft, "O.OO....")]
+    #[case::grow_center(8, Anchor::Center, "..O.OO..")]
+    #[case::grow_offset(8, Anchor::Offset(IVec3::new(3, 0, 0)), "...O.OO.")]
+    #[case::shrink_top_left(2, Anchor::TopLeft, "O.")]
+    #[case::shrink_center(2, Anchor::Center, ".O")]
+    #[case::shrink_offset(2, Anchor::Offset(IVec3::new(-2, 0, 0)), "OO")]
+    #[case::same_size_offset(4, Anchor::Offset(IVec3::new(1, 0, 0)), ".O.O")]
+    fn check_resize_1d(#[case] new_width: Index, #[case] anchor: Anchor, #[case] expected: &str) {
+        let mut grid = BitGrid::new_with_fn(4, 1, 1, |x, _y, _z| x != 1);
+        assert_eq!(grid.dims(), IVec3::new(4, 1, 1));
+
+        grid.resize_with_anchor(IVec3::new(new_width, 1, 1), anchor);
+        assert_eq!(grid.dims(), IVec3::new(new_width, 1, 1));
+
+        let ascii: String = (0..grid.width())
+            .map(|x| if grid.get(x, 0, 0) { 'O' } else { '.' })
+            .collect();
+        assert_eq!(ascii, expected);
+    }
+
+    #[test]
+    fn check_resize_2d_grow_and_shrink() {
+        let original = BitGrid::new_with_fn(3, 3, 1, |x, y, _z| x == y);
+
+        // Grow around the center: the diagonal moves to (1, 1)..(3, 3) in a 5x5
+        let mut grid = original.clone();
+        grid.resize_with_anchor(IVec3::new(5, 5, 1), Anchor::Center);
+        let expected = BitGrid::new_with_fn(5, 5, 1, |x, y, _z| x == y && (1..=3).contains(&x));
+        assert_eq!(grid, expected);
+
+        // Shrinking back around the center gets us the original
+        grid.resize_with_anchor(IVec3::new(3, 3, 1), Anchor::Center);
+        assert_eq!(grid, original);
+
+        // Only growing one axis via the trait keeps cells at their coordinates
+        let mut grid = original.clone();
+        Grid::resize(&mut grid, IVec3::new(3, 6, 1));
+        let expected = BitGrid::new_with_fn(3, 6, 1, |x, y, _z| x == y);
+        assert_eq!(grid, expected);
+    }
+
+    #[test]
+    fn check_resize_3d_round_trip() {
+        let original = BitGrid::new_with_fn(3, 4, 5, |x, y, z| (x + 2 * y + 3 * z) % 4 == 0);
+        let offset = IVec3::new(2, 1, 3);
+
+        let mut grid = original.clone();
+        grid.resize_with_anchor(IVec3::new(7, 6, 9), Anchor::Offset(offset));
+        assert_eq!(grid.count_set(), original.count_set());
+
+        for z in 0..grid.depth() {
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    let old = IVec3::new(x, y, z) - offset;
+                    let in_old = old.x >= 0
+                        && old.y >= 0
+                        && old.z >= 0
+                        && old.x < original.width()
+                        && old.y < original.height()
+                        && old.z < original.depth();
+                    let expected = in_old && original.get(old.x, old.y, old.z);
+                    assert_eq!(grid.get(x, y, z), expected, "Mismatch at ({x}, {y}, {z})");
+                }
+            }
+        }
+
+        grid.resize_with_anchor(original.dims(), Anchor::Offset(-offset));
+        assert_eq!(grid, original);
+    }
+
+    #[rstest]
+    #[case::bytes(16, 2, 1)]
+    #[case::partial_byte(3, 3, 1)]
+    #[case::cube(5, 5, 5)]
+    fn check_fill(#[case] width: usize, #[case] height: usize, #[case] depth: usize) {
+        let mut grid = BitGrid::new(width, height, depth);
+
+        grid.fill(true);
+        assert_eq!(grid.count_set(), width * height * depth);
+        assert_eq!(grid.get(0, 0, 0), true);
+
+        grid.fill(false);
+        assert!(grid.is_empty());
+        assert_eq!(grid.get(0, 0, 0), false);
+    }
+
+    #[rstest]
+    #[case::one_word(64, 3)]
+    #[case::unaligned(13, 7)]
+    #[case::multiple_words_unaligned(150, 5)]
+    fn check_read_write_row(#[case] width: usize, #[case] height: usize) {
+        let grid = BitGrid::new_with_fn(width, height, 2, |x, y, z| (x * 5 + y * 3 + z) % 7 < 3);
+        let mut copy = BitGrid::new(width, height, 2);
+        let mut words = vec![0; grid.words_per_row()];
+
+        for z in 0..grid.depth() {
+            for y in 0..grid.height() {
+                grid.read_row(y, z, &mut words);
+                for x in 0..grid.width() {
+                    let bit = (words[x as usize / 64] >> (x % 64)) & 1 != 0;
+                    assert_eq!(bit, grid.get(x, y, z), "Mismatch at ({x}, {y}, {z})");
+                }
+
+                // Bits past the end of the row stay clear
+                let last = words.len() - 1;
+                let used = width - 64 * last;
+                if used < 64 {
+                    assert_eq!(words[last] >> used, 0);
+                }
+
+                copy.write_row(y, z, &words);
+            }
+        }
+
+        assert_eq!(copy, grid);
+    }
+
+    #[test]
+    fn check_bounded_get_set() {
+        let mut grid = BitGrid::new(4, 4, 1).with_boundary(Boundary::Dead);
+
+        // Writes outside of a bounded grid go nowhere
+        assert_eq!(grid.set(-1, 0, 0, true), false);
+        assert_eq!(grid.flip(4, 0, 0), false);
+        assert!(grid.is_empty());
+        assert_eq!(grid.get(-1, 0, 0), false);
+
+        grid.set_boundary(Boundary::Alive);
+        assert_eq!(grid.get(-1, 0, 0), true);
+        assert_eq!(grid.get(0, 4, 0), true);
+        assert_eq!(grid.set(0, 4, 0, false), true);
+        assert!(grid.is_empty());
+
+        // Reflected writes land on the mirrored cell
+        grid.set_boundary(Boundary::Reflect);
+        grid.set(-1, -2, 0, true);
+        assert_eq!(grid.get(0, 1, 0), true);
+        assert_eq!(grid.count_set(), 1);
+    }
+
+    #[test]
+    fn check_resize_to_and_from_empty() {
+        let mut grid = BitGrid::new_with_fn(4, 4, 4, |_x, _y, _z| true);
+
+        grid.resize(IVec3::zero());
+        assert_eq!(grid.dims(), IVec3::zero());
+        assert!(grid.as_bytes().is_empty());
+
+        grid.resize(IVec3::new(2, 2, 2));
+        assert_eq!(grid.dims(), IVec3::new(2, 2, 2));
+        assert!(grid.is_empty());
+    }
 }
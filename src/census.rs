@@ -0,0 +1,601 @@
+use crate::grid::Resolved;
+use crate::prelude::*;
+use crate::{Apgcode, CycleDetector, Life, Pattern, PatternKind, PatternRule};
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Longest period of the objects a [`Census`] can identify, and of the soups it waits for
+const MAX_PERIOD: u64 = 30;
+
+/// A stable object found by a [`Census`]: a still life, oscillator, or spaceship.
+///
+/// Objects are kept in a canonical phase and orientation, so the same object always compares equal,
+/// however it was turned or whichever phase it was found in. This is the phase its [`apgcode`](Self::apgcode) is written from.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// # use simulations::{CensusObject, Pattern, PatternKind};
+/// // A blinker standing up is the same object as one lying down
+/// let tall = BitGrid::new_with_fn(1, 3, 1, |_, _, _| true);
+/// let blinker = CensusObject::identify(&tall, LifeRule::CONWAY).unwrap();
+///
+/// assert_eq!(blinker.kind(), PatternKind::Oscillator);
+/// assert_eq!(blinker.period(), 2);
+/// assert_eq!(blinker.name(), Some("Blinker"));
+/// assert_eq!(CensusObject::from_pattern(Pattern::Blinker), Some(blinker));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CensusObject {
+    kind: PatternKind,
+    period: u64,
+
+    /// The canonical phase, cropped to its alive cells
+    cells: BitGrid,
+}
+
+impl CensusObject {
+    /// Identifies the object made of the alive cells in the first layer of `cells`, when run alone under `rule`.
+    ///
+    /// Returns `None` if there are no alive cells, or if they don't repeat within 30 generations
+    /// starting from the very first, such as a pattern that is still settling down.
+    pub fn identify(cells: &BitGrid, rule: LifeRule) -> Option<Self> {
+        let cells = crop(cells)?;
+        let phases = evolve(&cells, rule, MAX_PERIOD);
+
+        let mut detector = CycleDetector::new(MAX_PERIOD as usize + 1);
+        let cycle = phases
+            .iter()
+            .enumerate()
+            .find_map(|(generation, grid)| detector.observe(generation as u64, grid))?;
+        if cycle.start != 0 {
+            return None;
+        }
+
+        let kind = if cycle.is_spaceship() {
+            PatternKind::Spaceship
+        } else if cycle.is_oscillator() {
+            PatternKind::Oscillator
+        } else {
+            PatternKind::StillLife
+        };
+
+        let cells = phases[..cycle.period as usize]
+            .iter()
+            .filter_map(crop)
+            .flat_map(|phase| Transform::ALL.map(|transform| phase.transformed(transform)))
+            .min_by_key(|phase| {
+                let code = crate::apgcode::encode(phase);
+                (code.len(), code)
+            })?;
+
+        Some(Self {
+            kind,
+            period: cycle.period,
+            cells,
+        })
+    }
+
+    /// Identifies a pattern from the [`Pattern`] library, if it is a stable object of a Life-like rule
+    pub fn from_pattern(pattern: Pattern) -> Option<Self> {
+        match (pattern.kind(), pattern.rule()) {
+            (
+                PatternKind::StillLife | PatternKind::Oscillator | PatternKind::Spaceship,
+                PatternRule::Life(rule),
+            ) => Self::identify(&pattern.grid(), rule),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> PatternKind {
+        self.kind
+    }
+
+    /// Number of generations before the object repeats
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// The object's cells in its canonical phase and orientation
+    pub fn cells(&self) -> &BitGrid {
+        &self.cells
+    }
+
+    /// Number of alive cells in the canonical phase
+    pub fn population(&self) -> usize {
+        self.cells.count_set()
+    }
+
+    /// The name of this object, if it is in the [`Pattern`] library
+    pub fn name(&self) -> Option<&'static str> {
+        Pattern::ALL
+            .into_iter()
+            .find(|&pattern| Self::from_pattern(pattern).as_ref() == Some(self))
+            .map(Pattern::name)
+    }
+
+    /// The canonical name of this object in the apgcode format. See [`Apgcode`].
+    pub fn apgcode(&self) -> Apgcode {
+        Apgcode {
+            kind: self.kind,
+            period: self.period,
+            grid: self.cells.clone(),
+        }
+    }
+
+    /// Orders objects by population, then period, then their cells
+    fn sort_key(&self) -> (usize, u64, Index, Index, &[u8]) {
+        let cells = &self.cells;
+        (
+            cells.count_set(),
+            self.period,
+            cells.width(),
+            cells.height(),
+            cells.as_bytes(),
+        )
+    }
+}
+
+impl PartialOrd for CensusObject {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The kind of an object follows from its cells and period, so it doesn't need to be compared
+impl Ord for CensusObject {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+/// Counts of the objects left behind once a soup of Life cells settles down, similar to what
+/// [apgsearch](https://conwaylife.com/wiki/Apgsearch) reports.
+///
+/// A census waits for the population to start repeating, then splits the cells into separate objects
+/// and identifies each one by running it alone. Cells are grouped into an object when they are at most 2 cells apart,
+/// then split again if the groups don't affect each other, so a pair of blocks side by side counts as two blocks.
+/// Objects crossing the edge of a wrapping grid are put back together.
+///
+/// Groups of cells that aren't stable on their own, such as a glider about to crash, are counted as [`unidentified`](Self::unidentified).
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Census, Life};
+/// # use rand::{rngs::SmallRng, SeedableRng};
+/// let mut rng = SmallRng::seed_from_u64(1);
+/// let mut total = Census::new();
+///
+/// for _ in 0..4 {
+///     let mut life: Life = Life::new(32, 32);
+///     life.clear_random(&mut rng);
+///     if let Some(census) = Census::take(&mut life, 2000) {
+///         total.add(&census);
+///     }
+/// }
+///
+/// for (object, count) in total.iter() {
+///     println!("{}: {count}", object.apgcode());
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    counts: BTreeMap<CensusObject, u64>,
+    unidentified: u64,
+}
+
+impl Census {
+    /// Creates an empty census
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Steps `life` until its population repeats, then counts the objects in it.
+    ///
+    /// Returns `None` if the population hasn't started repeating after `max_generations` steps.
+    pub fn take(life: &mut Life, max_generations: u64) -> Option<Self> {
+        stabilise(life, max_generations)?;
+
+        let mut census = Self::new();
+        for cluster in clusters(life.cells(), life.boundary()) {
+            for part in split(&cluster, life.rule()) {
+                match CensusObject::identify(&part, life.rule()) {
+                    Some(object) => census.insert(object),
+                    None => census.unidentified += 1,
+                }
+            }
+        }
+        Some(census)
+    }
+
+    /// Counts one more of `object`
+    pub fn insert(&mut self, object: CensusObject) {
+        *self.counts.entry(object).or_default() += 1;
+    }
+
+    /// Adds every count of `other` into this census
+    pub fn add(&mut self, other: &Census) {
+        for (object, &count) in &other.counts {
+            *self.counts.entry(object.clone()).or_default() += count;
+        }
+        self.unidentified += other.unidentified;
+    }
+
+    /// Number of times `object` was found
+    pub fn count(&self, object: &CensusObject) -> u64 {
+        self.counts.get(object).copied().unwrap_or(0)
+    }
+
+    /// Number of times the [`Pattern`] with `name` was found. See [`Pattern::from_name`].
+    pub fn count_named(&self, name: &str) -> u64 {
+        Pattern::from_name(name)
+            .and_then(CensusObject::from_pattern)
+            .map_or(0, |object| self.count(&object))
+    }
+
+    /// Number of groups of cells that weren't stable objects on their own
+    pub fn unidentified(&self) -> u64 {
+        self.unidentified
+    }
+
+    /// Number of objects found, of every type
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Number of different types of object found
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty() && self.unidentified == 0
+    }
+
+    /// Every type of object found with its count, smallest objects first
+    pub fn iter(&self) -> impl Iterator<Item = (&CensusObject, u64)> + '_ {
+        self.counts.iter().map(|(object, &count)| (object, count))
+    }
+}
+
+/// Steps `life` until its population has repeated with some period a few times over, returning that period
+fn stabilise(life: &mut Life, max_generations: u64) -> Option<u64> {
+    let history = 4 * MAX_PERIOD as usize;
+    let mut populations = VecDeque::with_capacity(history + 1);
+    populations.push_back(life.cells().count_set());
+
+    for steps in 0..=max_generations {
+        if let Some(period) = population_period(&populations) {
+            return Some(period);
+        }
+        if steps == max_generations {
+            break;
+        }
+
+        life.step();
+        populations.push_back(life.cells().count_set());
+        if populations.len() > history {
+            populations.pop_front();
+        }
+    }
+    None
+}
+
+/// The smallest period the most recent populations have repeated with, at least 3 times over
+fn population_period(populations: &VecDeque<usize>) -> Option<u64> {
+    let n = populations.len();
+    (1..=MAX_PERIOD as usize)
+        .find(|&period| {
+            let checked = (3 * period).max(12);
+            n >= checked + period
+                && (0..checked).all(|i| populations[n - 1 - i] == populations[n - 1 - i - period])
+        })
+        .map(|period| period as u64)
+}
+
+/// Groups the alive cells of `grid` into clusters where each cell is at most 2 cells from another.
+///
+/// Each cluster is cropped into its own grid. Cells across the edges of `grid` are found with `boundary`.
+fn clusters(grid: &BitGrid, boundary: Boundary) -> Vec<BitGrid> {
+    let dims = grid.dims();
+    let mut seen = BitGrid::new(dims.x as usize, dims.y as usize, 1);
+    let mut clusters = Vec::new();
+
+    for y in 0..dims.y {
+        for x in 0..dims.x {
+            if !grid.get(x, y, 0) || seen.get(x, y, 0) {
+                continue;
+            }
+
+            // Positions are kept as they were reached, so clusters crossing an edge stay in one piece
+            seen.set(x, y, 0, true);
+            let mut cells = vec![IVec3::new(x, y, 0)];
+            let mut next = 0;
+            while let Some(&pos) = cells.get(next) {
+                next += 1;
+                for dy in -2..=2 {
+                    for dx in -2..=2 {
+                        let near = pos + IVec3::new(dx, dy, 0);
+                        let Resolved::Cell(cell) = boundary.resolve(dims, near) else {
+                            continue;
+                        };
+                        if grid.get(cell.x, cell.y, 0) && !seen.get(cell.x, cell.y, 0) {
+                            seen.set(cell.x, cell.y, 0, true);
+                            // Only wrapping edges carry on past the grid. Reflected cells are already in bounds.
+                            cells.push(match boundary {
+                                Boundary::Wrap | Boundary::Klein => near,
+                                _ => cell,
+                            });
+                        }
+                    }
+                }
+            }
+
+            clusters.push(grid_of(&cells));
+        }
+    }
+    clusters
+}
+
+/// Splits `cluster` into its 8-connected pieces if they evolve the same apart as together, or else keeps it whole
+fn split(cluster: &BitGrid, rule: LifeRule) -> Vec<BitGrid> {
+    let dims = cluster.dims();
+    let mut seen = BitGrid::new(dims.x as usize, dims.y as usize, 1);
+    let mut pieces = Vec::new();
+
+    for y in 0..dims.y {
+        for x in 0..dims.x {
+            if !cluster.get(x, y, 0) || seen.get(x, y, 0) {
+                continue;
+            }
+
+            let mut piece = BitGrid::new(dims.x as usize, dims.y as usize, 1);
+            let mut stack = vec![IVec3::new(x, y, 0)];
+            seen.set(x, y, 0, true);
+            while let Some(pos) = stack.pop() {
+                piece.set(pos.x, pos.y, 0, true);
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let near = pos + IVec3::new(dx, dy, 0);
+                        let in_bounds =
+                            (0..dims.x).contains(&near.x) && (0..dims.y).contains(&near.y);
+                        if in_bounds
+                            && cluster.get(near.x, near.y, 0)
+                            && !seen.get(near.x, near.y, 0)
+                        {
+                            seen.set(near.x, near.y, 0, true);
+                            stack.push(near);
+                        }
+                    }
+                }
+            }
+            pieces.push(piece);
+        }
+    }
+
+    if pieces.len() < 2 {
+        return vec![cluster.clone()];
+    }
+
+    let together = evolve(cluster, rule, MAX_PERIOD);
+    let mut apart = evolve(&pieces[0], rule, MAX_PERIOD);
+    for piece in &pieces[1..] {
+        for (sum, phase) in apart.iter_mut().zip(evolve(piece, rule, MAX_PERIOD)) {
+            for (a, b) in sum.as_mut_bytes().iter_mut().zip(phase.as_bytes()) {
+                *a |= b;
+            }
+        }
+    }
+
+    if together == apart {
+        pieces
+    } else {
+        vec![cluster.clone()]
+    }
+}
+
+/// Runs `cells` alone under `rule`, returning every generation from `0` to `generations`.
+///
+/// The cells are surrounded by enough **dead** cells that even the fastest spaceships never reach the edge.
+fn evolve(cells: &BitGrid, rule: LifeRule, generations: u64) -> Vec<BitGrid> {
+    let margin = generations as Index / 2 + 2;
+    let grid = BitGrid::new(
+        (cells.width() + 2 * margin) as usize,
+        (cells.height() + 2 * margin) as usize,
+        1,
+    )
+    .with_boundary(Boundary::Dead);
+
+    let mut life = Life::new_with_cells_and_rule(rule, grid);
+    life.cells_mut()
+        .stamp(cells, IVec3::new(margin, margin, 0), Transform::Identity);
+
+    let mut phases = vec![life.cells().clone()];
+    for _ in 0..generations {
+        life.step();
+        phases.push(life.cells().clone());
+    }
+    phases
+}
+
+/// The alive cells of the first layer of `grid`, cropped to their bounding box. `None` if there are none.
+fn crop(grid: &BitGrid) -> Option<BitGrid> {
+    let mut cells = Vec::new();
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            if grid.get(x, y, 0) {
+                cells.push(IVec3::new(x, y, 0));
+            }
+        }
+    }
+    (!cells.is_empty()).then(|| grid_of(&cells))
+}
+
+/// A grid just big enough to hold `cells`, with them set
+fn grid_of(cells: &[IVec3]) -> BitGrid {
+    let min = cells
+        .iter()
+        .fold(cells[0], |min, &pos| min.min_by_component(pos));
+    let max = cells
+        .iter()
+        .fold(cells[0], |max, &pos| max.max_by_component(pos));
+    let size = max - min + IVec3::one();
+
+    let mut grid = BitGrid::new(size.x as usize, size.y as usize, 1);
+    for &pos in cells {
+        let pos = pos - min;
+        grid.set(pos.x, pos.y, 0, true);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, SeedableRng};
+    use rstest::*;
+
+    #[rstest]
+    #[case::block(Pattern::Block)]
+    #[case::beehive(Pattern::Beehive)]
+    #[case::boat(Pattern::Boat)]
+    #[case::toad(Pattern::Toad)]
+    #[case::pulsar(Pattern::Pulsar)]
+    #[case::glider(Pattern::Glider)]
+    #[case::lwss(Pattern::Lwss)]
+    fn check_identify_ignores_phase_and_orientation(#[case] pattern: Pattern) {
+        let expected = CensusObject::from_pattern(pattern).unwrap();
+        assert_eq!(expected.kind(), pattern.kind());
+        assert_eq!(Some(expected.period()), pattern.period());
+        assert_eq!(expected.name(), Some(pattern.name()));
+
+        let phases = evolve(&pattern.grid(), LifeRule::CONWAY, expected.period());
+        for phase in &phases {
+            for transform in Transform::ALL {
+                let turned = phase.transformed(transform);
+                assert_eq!(
+                    CensusObject::identify(&turned, LifeRule::CONWAY).as_ref(),
+                    Some(&expected),
+                    "{pattern} {transform:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn check_identify_unstable() {
+        let r_pentomino = Pattern::RPentomino.grid();
+        assert_eq!(CensusObject::identify(&r_pentomino, LifeRule::CONWAY), None);
+        assert_eq!(
+            CensusObject::identify(&BitGrid::new(4, 4, 1), LifeRule::CONWAY),
+            None
+        );
+
+        // Nothing survives under Seeds, so not even a block is stable
+        assert_eq!(
+            CensusObject::identify(&Pattern::Block.grid(), LifeRule::SEEDS),
+            None
+        );
+    }
+
+    #[test]
+    fn check_census_of_known_objects() {
+        let mut life: Life = Life::new(32, 32);
+        let mut place = |pattern: Pattern, x: Index, y: Index, transform: Transform| {
+            let at = IVec3::new(x, y, 0);
+            life.cells_mut().stamp(&pattern.grid(), at, transform);
+        };
+
+        place(Pattern::Block, 2, 2, Transform::Identity);
+        place(Pattern::Blinker, 10, 2, Transform::Identity);
+        place(Pattern::Blinker, 20, 8, Transform::Rotate90);
+        place(Pattern::Beehive, 4, 20, Transform::Transpose);
+        place(Pattern::Glider, 24, 24, Transform::Identity);
+
+        // Two blocks with a one cell gap are close enough to be grouped, but don't affect each other
+        place(Pattern::Block, 12, 12, Transform::Identity);
+        place(Pattern::Block, 15, 12, Transform::Identity);
+
+        // A block across the left and right edges
+        place(Pattern::Block, 31, 5, Transform::Identity);
+
+        let census = Census::take(&mut life, 100).unwrap();
+        let counts: Vec<_> = census
+            .iter()
+            .map(|(object, count)| (object.name().unwrap(), count))
+            .collect();
+        assert_eq!(
+            counts,
+            [("Blinker", 2), ("Block", 4), ("Glider", 1), ("Beehive", 1)]
+        );
+        assert_eq!(census.unidentified(), 0);
+        assert_eq!(census.count_named("block"), 4);
+        assert_eq!(census.count_named("pulsar"), 0);
+        assert_eq!(census.total(), 8);
+    }
+
+    #[rstest]
+    #[case::dead(Boundary::Dead, &[(0, 3), (0, 4)], (1, 2))]
+    #[case::reflect(Boundary::Reflect, &[(0, 3), (0, 4)], (1, 2))]
+    #[case::reflect_corner(Boundary::Reflect, &[(1, 0), (0, 1)], (2, 2))]
+    #[case::wrap(Boundary::Wrap, &[(0, 3), (7, 3)], (2, 1))]
+    fn check_clusters_on_edges(
+        #[case] boundary: Boundary,
+        #[case] alive: &[(Index, Index)],
+        #[case] size: (Index, Index),
+    ) {
+        let mut grid = BitGrid::new(8, 8, 1);
+        for &(x, y) in alive {
+            grid.set(x, y, 0, true);
+        }
+
+        let found = clusters(&grid, boundary);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].width(), found[0].height()), size);
+        assert_eq!(found[0].count_set(), alive.len());
+    }
+
+    #[test]
+    fn check_census_of_soups() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut total = Census::new();
+
+        for _ in 0..3 {
+            let mut life: Life = Life::new(48, 48);
+            life.clear_random(&mut rng);
+            let census = Census::take(&mut life, 5000).unwrap();
+            total.add(&census);
+        }
+
+        assert!(total.count_named("block") > 0);
+        for (object, _) in total.iter() {
+            assert_eq!(
+                CensusObject::identify(object.cells(), LifeRule::CONWAY).as_ref(),
+                Some(object)
+            );
+        }
+    }
+
+    #[test]
+    fn check_population_period() {
+        let repeat = |pattern: &[usize], n: usize| -> VecDeque<usize> {
+            pattern.iter().copied().cycle().take(n).collect()
+        };
+
+        assert_eq!(population_period(&repeat(&[5], 20)), Some(1));
+        assert_eq!(population_period(&repeat(&[5], 8)), None);
+        assert_eq!(population_period(&repeat(&[3, 6, 4], 40)), Some(3));
+
+        let mut growing: VecDeque<usize> = (0..100).collect();
+        assert_eq!(population_period(&growing), None);
+        growing.extend([7; 12]);
+        assert_eq!(population_period(&growing), None);
+        growing.push_back(7);
+        assert_eq!(population_period(&growing), Some(1));
+    }
+}
@@ -0,0 +1,325 @@
+use crate::prelude::*;
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec;
+
+/// A repeating loop found by a [`CycleDetector`]
+///
+/// After [`start`](Self::start) generations, the cells repeat every [`period`](Self::period) generations,
+/// moved by [`offset`](Self::offset) each time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The first generation that is part of the cycle
+    pub start: u64,
+
+    /// Number of generations before the cells repeat
+    pub period: u64,
+
+    /// How far the cells move every period. This is zero for still lifes and oscillators.
+    pub offset: IVec3,
+}
+
+impl Cycle {
+    /// Whether the cells never change again
+    pub fn is_still_life(&self) -> bool {
+        self.period == 1 && self.offset == IVec3::zero()
+    }
+
+    /// Whether the cells change, but return to the same place every period
+    pub fn is_oscillator(&self) -> bool {
+        self.period > 1 && self.offset == IVec3::zero()
+    }
+
+    /// Whether the cells move every period
+    pub fn is_spaceship(&self) -> bool {
+        self.offset != IVec3::zero()
+    }
+}
+
+/// Detects when a sequence of grids starts repeating, by remembering a hash of each of the last few generations.
+///
+/// Every grid is hashed relative to the minimum corner of its alive cells, and the corner is kept alongside the hash.
+/// When a generation matches an earlier hash with its corner in the same place, the cells are oscillating (or frozen).
+/// When the corner has moved, the cells are a spaceship moving by the difference in corners.
+///
+/// Only the last [`capacity`](Self::capacity) generations are remembered, so cycles longer than that can't be found.
+/// Hashes are 64 bits, so two different grids are very unlikely, but not impossible, to be mistaken for each other.
+///
+/// Spaceships on a wrapping grid are found before they reach an edge. Once they cross one, their corner jumps
+/// and they are only found again after a full lap, as an oscillator.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{CycleDetector, Life};
+/// # use simulations::prelude::IVec3;
+/// let mut life: Life = Life::new(32, 32);
+/// life.write_right_glider(1, 1);
+///
+/// let mut detector = CycleDetector::new(16);
+/// let cycle = loop {
+///     if let Some(cycle) = detector.observe(life.generation(), life.cells()) {
+///         break cycle;
+///     }
+///     life.step();
+/// };
+///
+/// assert_eq!(cycle.period, 4);
+/// assert_eq!(cycle.offset, IVec3::new(1, 1, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CycleDetector {
+    /// Most recent generations, oldest first
+    history: VecDeque<Entry>,
+
+    /// The most recent generation with each translation-invariant hash
+    by_shape: BTreeMap<u64, Entry>,
+
+    capacity: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    generation: u64,
+    shape: u64,
+    corner: IVec3,
+}
+
+impl CycleDetector {
+    /// Creates a detector that remembers the last `capacity` generations
+    ///
+    /// # Panics
+    /// If `capacity` is `0`.
+    #[track_caller]
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "A CycleDetector must remember at least one generation"
+        );
+        Self {
+            history: VecDeque::with_capacity(capacity),
+            by_shape: BTreeMap::new(),
+            capacity,
+        }
+    }
+
+    /// Number of generations remembered, which is the longest period that can be found
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forgets every generation seen so far
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.by_shape.clear();
+    }
+
+    /// Remembers `grid` as the state at `generation`, returning the cycle it completes, if any.
+    ///
+    /// Generations should be observed in increasing order, usually one after every step.
+    pub fn observe<G: Grid>(&mut self, generation: u64, grid: &G) -> Option<Cycle> {
+        let (shape, corner) = shape_hash(grid);
+        let entry = Entry {
+            generation,
+            shape,
+            corner,
+        };
+
+        let cycle = self.by_shape.get(&shape).map(|earlier| Cycle {
+            start: earlier.generation,
+            period: generation - earlier.generation,
+            offset: corner - earlier.corner,
+        });
+
+        if self.history.len() == self.capacity {
+            let oldest = self.history.pop_front().unwrap();
+            if self.by_shape.get(&oldest.shape) == Some(&oldest) {
+                self.by_shape.remove(&oldest.shape);
+            }
+        }
+        self.history.push_back(entry);
+        self.by_shape.insert(shape, entry);
+
+        cycle
+    }
+}
+
+/// Hashes the alive cells of `grid` relative to the minimum corner of their bounding box, returning the hash and that corner.
+///
+/// The hash doesn't depend on the order cells are visited in, so unordered grids like [`SparseGrid`] hash consistently.
+/// An empty grid hashes to `0` with its corner at the origin.
+pub(crate) fn shape_hash<G: Grid>(grid: &G) -> (u64, IVec3) {
+    let mut corner: Option<IVec3> = None;
+    for_each_alive(grid, |pos| {
+        corner = Some(corner.map_or(pos, |c| c.min_by_component(pos)));
+    });
+    let Some(corner) = corner else {
+        return (0, IVec3::zero());
+    };
+
+    let mut sum = 0_u64;
+    let mut xor = 0_u64;
+    for_each_alive(grid, |pos| {
+        let pos = pos - corner;
+        let key =
+            (pos.x as u32 as u64) ^ ((pos.y as u32 as u64) << 21) ^ ((pos.z as u32 as u64) << 42);
+        let h = mix(key);
+        sum = sum.wrapping_add(h);
+        xor ^= mix(h);
+    });
+
+    (mix(sum ^ xor.rotate_left(32)), corner)
+}
+
+/// Calls `f` with the position of every **alive** cell in `grid`, in no particular order
+fn for_each_alive<G: Grid>(grid: &G, mut f: impl FnMut(IVec3)) {
+    if let Some(bits) = grid.as_bitgrid() {
+        let mut row = vec![0_u64; bits.words_per_row()];
+        for z in 0..bits.depth() {
+            for y in 0..bits.height() {
+                bits.read_row(y, z, &mut row);
+                for (k, &word) in row.iter().enumerate() {
+                    let mut word = word;
+                    while word != 0 {
+                        let x = 64 * k as Index + word.trailing_zeros() as Index;
+                        f(IVec3::new(x, y, z));
+                        word &= word - 1;
+                    }
+                }
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "std")]
+    if let Some(sparse) = grid.as_sparse() {
+        sparse.iter().for_each(f);
+        return;
+    }
+
+    let (min, max) = grid.extent();
+    for z in min.z..max.z {
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                if grid.get(x, y, z) {
+                    f(IVec3::new(x, y, z));
+                }
+            }
+        }
+    }
+}
+
+/// The `splitmix64` finalizer, which spreads every input bit across the whole output
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Elementry, Life};
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    fn life_with(width: usize, height: usize, cells: &[(i16, i16)]) -> Life {
+        let mut life = Life::new(width, height);
+        for &(x, y) in cells {
+            life.set(x, y, true);
+        }
+        life
+    }
+
+    #[rstest]
+    #[case::block(&[(1, 1), (2, 1), (1, 2), (2, 2)], 0, 1, IVec3::zero())]
+    #[case::blinker(&[(2, 1), (2, 2), (2, 3)], 0, 2, IVec3::zero())]
+    #[case::glider(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)], 0, 4, IVec3::new(1, 1, 0))]
+    #[case::lwss(
+        &[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)],
+        0,
+        4,
+        IVec3::new(-2, 0, 0),
+    )]
+    // A pre-block: three cells that become a block after one step
+    #[case::pre_block(&[(1, 1), (2, 1), (1, 2)], 1, 1, IVec3::zero())]
+    fn check_life_cycles(
+        #[case] cells: &[(i16, i16)],
+        #[case] start: u64,
+        #[case] period: u64,
+        #[case] offset: IVec3,
+    ) {
+        // Start spaceships away from the edges, so they don't wrap before repeating
+        let cells: alloc::vec::Vec<_> = cells.iter().map(|&(x, y)| (x + 10, y + 10)).collect();
+        let mut life = life_with(32, 32, &cells);
+
+        let cycle = life.find_cycle(100, 16);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start,
+                period,
+                offset
+            })
+        );
+        assert_eq!(life.generation(), start + period);
+    }
+
+    #[test]
+    fn check_history_too_short() {
+        // A blinker's period of 2 can't be seen with only 1 generation of history
+        let mut life = life_with(16, 16, &[(2, 1), (2, 2), (2, 3)]);
+        assert_eq!(life.find_cycle(10, 1), None);
+        assert_eq!(life.generation(), 10);
+    }
+
+    #[test]
+    fn check_elementry_cycles() {
+        // Rule 170 shifts every cell one to the left
+        let mut shift: Elementry = Elementry::new(170, 16);
+        shift.set(5, true);
+        shift.set(7, true);
+
+        let cycle = shift.find_cycle(10, 4).unwrap();
+        assert_eq!(cycle.offset, IVec3::new(-1, 0, 0));
+        assert_eq!((cycle.start, cycle.period), (0, 1));
+        assert!(cycle.is_spaceship());
+
+        // Rule 30 on a small ring falls into a loop eventually
+        let mut rule_30: Elementry = Elementry::new(30, 11);
+        rule_30.set(5, true);
+
+        let cycle = rule_30.find_cycle(2048, 2048).unwrap();
+        let mut twin: Elementry = Elementry::new(30, 11);
+        twin.set(5, true);
+        for _ in 0..cycle.start {
+            twin.step();
+        }
+        let start: alloc::vec::Vec<bool> = twin.cells().collect();
+        for _ in 0..cycle.period {
+            twin.step();
+        }
+        let end: alloc::vec::Vec<bool> = twin.cells().collect();
+
+        // On a wrapping ring a shifted pattern can look like a spaceship, so compare the shape after shifting
+        let dx = cycle.offset.x.rem_euclid(11) as usize;
+        let shifted: alloc::vec::Vec<bool> = (0..11).map(|x| start[(x + 11 - dx) % 11]).collect();
+        assert_eq!(end, shifted);
+    }
+
+    #[test]
+    fn check_hash_ignores_grid_type_and_position() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let bits = life_with(16, 16, &glider).into_cells();
+        let moved = life_with(16, 16, &glider.map(|(x, y)| (x + 5, y + 3))).into_cells();
+        let sparse = SparseGrid::from_bitgrid(&bits, IVec3::new(-40, 7, 0));
+
+        let (hash, corner) = shape_hash(&bits);
+        assert_eq!(corner, IVec3::zero());
+        assert_eq!(shape_hash(&moved), (hash, IVec3::new(5, 3, 0)));
+        assert_eq!(shape_hash(&sparse), (hash, IVec3::new(-40, 7, 0)));
+
+        assert_eq!(shape_hash(&BitGrid::new(4, 4, 1)), (0, IVec3::zero()));
+    }
+}
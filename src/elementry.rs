@@ -1,4 +1,9 @@
 use crate::prelude::*;
+use crate::stats::StatsRecorder;
+use crate::{Preimages, SpaceTime, StepStats, TimeSeries};
+
+use alloc::vec;
+use alloc::vec::Vec;
 
 #[derive(Clone)]
 pub struct Elementry<G: Grid = crate::BitGrid> {
@@ -9,6 +14,18 @@ pub struct Elementry<G: Grid = crate::BitGrid> {
     scratch: G,
 
     rule: u8,
+
+    /// How cells beyond the ends of `cells` are treated
+    boundary: Boundary,
+
+    /// Number of steps taken since creation or the last reset
+    generation: u64,
+
+    /// Stats of every step, while they're being recorded
+    stats: StatsRecorder,
+
+    /// The most recent generations, while they're being recorded
+    history: Option<SpaceTime>,
 }
 
 /// Basic Usage
@@ -38,36 +55,263 @@ impl<G: Grid + Clone> Elementry<G> {
         );
 
         let scratch = cells.clone();
+        let boundary = cells.boundary();
         Self {
             cells,
             scratch,
             rule,
+            boundary,
+            generation: 0,
+            stats: StatsRecorder::default(),
+            history: None,
         }
     }
 }
 
 impl<G: Grid> Elementry<G> {
     /// The width of the simulation
-    pub fn width(&self) -> i16 {
-        self.cells.width() as i16
+    pub fn width(&self) -> Index {
+        self.cells.width()
     }
 
     pub fn cells(&self) -> impl Iterator<Item = bool> + '_ {
         (0..self.width()).map(|i| self.get(i))
     }
 
-    pub fn get(&self, x: i16) -> bool {
-        self.cells.get(x as Index, 1, 1)
+    /// Borrow the backing `Grid` object
+    pub fn grid(&self) -> &G {
+        &self.cells
+    }
+
+    /// Mutably borrow the backing `Grid` object
+    pub(crate) fn grid_mut(&mut self) -> &mut G {
+        &mut self.cells
+    }
+
+    /// How many generations have been stepped through since this simulation was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn rule(&self) -> ElementryRule {
+        ElementryRule::new(self.rule)
+    }
+
+    /// How cells beyond the ends of the simulation are treated
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how cells beyond the ends of the simulation are treated.
+    ///
+    /// This overrides the [`Boundary`] of the underlying grid for this simulation only.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
     }
 
-    pub fn set(&mut self, x: i16, is_alive: bool) {
-        self.cells.set(x as Index, 1, 1, is_alive);
+    /// Checks whether the cell at `x` is **alive** or **dead**.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    pub fn get(&self, x: Index) -> bool {
+        self.cells.get_with_boundary(x, 0, 0, self.boundary)
+    }
+
+    /// Sets the cell at `x` to either **alive** or **dead**.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells outside of a bounded simulation are ignored.
+    pub fn set(&mut self, x: Index, is_alive: bool) {
+        self.cells
+            .set_with_boundary(x, 0, 0, is_alive, self.boundary);
     }
 
     /// Steps the simulation once, returning the number of cells updated
     ///
     /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
     pub fn step(&mut self) -> u32 {
+        if self.stats.is_recording() {
+            return self.step_with_stats().changed();
+        }
+
+        self.step_cells()
+    }
+
+    /// Steps the simulation once like [`step`](Self::step), returning how the cells changed.
+    ///
+    /// This compares every cell before and after the step, so it is slower than `step`.
+    pub fn step_with_stats(&mut self) -> StepStats {
+        self.step_cells();
+        self.stats
+            .record(self.generation, &self.scratch, &self.cells)
+    }
+
+    /// Starts or stops recording the [`StepStats`] of every step into a [`TimeSeries`].
+    ///
+    /// While recording, [`step`](Self::step) is as slow as [`step_with_stats`](Self::step_with_stats).
+    /// Stopping throws away everything recorded so far.
+    pub fn record_stats(&mut self, record: bool) {
+        self.stats.set_recording(record);
+    }
+
+    /// The stats recorded since [`record_stats`](Self::record_stats) was turned on, if it is
+    pub fn time_series(&self) -> Option<&TimeSeries> {
+        self.stats.series()
+    }
+
+    /// Stops recording stats, returning everything recorded so far
+    pub fn take_time_series(&mut self) -> Option<TimeSeries> {
+        self.stats.take()
+    }
+
+    /// Starts recording the last `capacity` generations into a [`SpaceTime`] diagram, beginning with the current one.
+    ///
+    /// Every step adds a row, replacing the oldest once `capacity` rows are held.
+    /// Passing `None` stops recording and throws away everything recorded so far.
+    ///
+    /// # Panics
+    /// If `capacity` is `Some(0)`.
+    pub fn record_history(&mut self, capacity: Option<usize>) {
+        self.history = capacity.map(|capacity| {
+            let mut history = SpaceTime::new(self.cells.width() as usize, capacity);
+            history.push(self.generation, &self.cells);
+            history
+        });
+    }
+
+    /// The generations recorded since [`record_history`](Self::record_history) was turned on, if it is
+    pub fn history(&self) -> Option<&SpaceTime> {
+        self.history.as_ref()
+    }
+
+    /// Stops recording generations, returning everything recorded so far
+    pub fn take_history(&mut self) -> Option<SpaceTime> {
+        self.history.take()
+    }
+
+    /// Steps the simulation `generations - 1` times, returning a diagram of every generation
+    /// from the current one to the last.
+    ///
+    /// # Panics
+    /// If `generations` is `0`.
+    pub fn space_time(&mut self, generations: usize) -> SpaceTime {
+        let mut diagram = SpaceTime::new(self.cells.width() as usize, generations);
+        diagram.push(self.generation, &self.cells);
+        for _ in 1..generations {
+            self.step();
+            diagram.push(self.generation, &self.cells);
+        }
+        diagram
+    }
+
+    /// Whether [`jump`](Self::jump) can skip straight to a later generation without stepping through the ones between.
+    ///
+    /// This needs an [additive](ElementryRule::is_additive) rule on a [wrapping](Boundary::Wrap) ring,
+    /// and nothing being recorded, since recordings need every generation.
+    pub fn can_jump(&self) -> bool {
+        self.rule().is_additive()
+            && self.boundary == Boundary::Wrap
+            && !self.stats.is_recording()
+            && self.history.is_none()
+    }
+
+    /// Moves the simulation `generations` steps forward, ending up where [`step`](Self::step) would.
+    ///
+    /// If [`can_jump`](Self::can_jump), this takes time in proportion to `width * log(generations)`,
+    /// so it can go billions of generations ahead at once. Otherwise it steps one generation at a time.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::Elementry;
+    /// let mut rule_90: Elementry = Elementry::new(90, 100);
+    /// rule_90.set(50, true);
+    /// let mut stepped = rule_90.clone();
+    ///
+    /// rule_90.jump(1_000);
+    /// for _ in 0..1_000 {
+    ///     stepped.step();
+    /// }
+    /// assert_eq!(rule_90.grid(), stepped.grid());
+    /// assert_eq!(rule_90.generation(), 1_000);
+    /// ```
+    pub fn jump(&mut self, generations: u64) {
+        let terms = match self.rule().additive_terms() {
+            Some(terms) if self.can_jump() => terms,
+            _ => {
+                for _ in 0..generations {
+                    self.step();
+                }
+                return;
+            }
+        };
+
+        let width = self.cells.width() as usize;
+        let mut words = vec![0; width.div_ceil(64)];
+        if let Some(cells) = self.cells.as_bitgrid() {
+            cells.read_row(0, 0, &mut words);
+        } else {
+            for x in 0..width {
+                words[x / 64] |= (self.cells.get(x as Index, 0, 0) as u64) << (x % 64);
+            }
+        }
+
+        jump_words(&mut words, width, terms, generations);
+
+        if let Some(cells) = self.cells.as_bitgrid_mut() {
+            cells.write_row(0, 0, &words);
+        } else {
+            for x in 0..width {
+                let is_alive = (words[x / 64] >> (x % 64)) & 1 != 0;
+                self.cells.set(x as Index, 0, 0, is_alive);
+            }
+        }
+        self.generation += generations;
+    }
+
+    /// Every row that becomes the current cells after one step. See [`ElementryRule::preimages`].
+    ///
+    /// The cells are treated as a ring, whatever the [`boundary`](Self::boundary) is.
+    pub fn preimages(&self) -> Preimages {
+        self.rule().preimages(&self.row())
+    }
+
+    /// Number of rows that become the current cells after one step. See [`ElementryRule::count_preimages`].
+    ///
+    /// The cells are treated as a ring, whatever the [`boundary`](Self::boundary) is.
+    pub fn count_preimages(&self) -> u128 {
+        self.rule().count_preimages(&self.row())
+    }
+
+    /// Whether no row becomes the current cells after one step. See [`ElementryRule::is_garden_of_eden`].
+    ///
+    /// The cells are treated as a ring, whatever the [`boundary`](Self::boundary) is.
+    pub fn is_garden_of_eden(&self) -> bool {
+        self.rule().is_garden_of_eden(&self.row())
+    }
+
+    /// Every cell, from left to right
+    fn row(&self) -> Vec<bool> {
+        (0..self.cells.width())
+            .map(|x| self.cells.get(x, 0, 0))
+            .collect()
+    }
+
+    /// Steps `cells` once with the fastest method for its grid, returning the number of cells updated.
+    ///
+    /// Afterwards, `scratch` holds the previous generation.
+    fn step_cells(&mut self) -> u32 {
+        if let (Some(cells), Some(scratch)) =
+            (self.cells.as_bitgrid(), self.scratch.as_bitgrid_mut())
+        {
+            let count = step_words(cells, scratch, self.rule, self.boundary);
+            self.finish_step();
+            return count;
+        }
+
+        self.step_generic()
+    }
+
+    /// Steps the simulation once, one cell at a time with [`Grid::get`]. This works for any grid.
+    fn step_generic(&mut self) -> u32 {
         let mut count = 0;
 
         // Modify scratch while we step because we must keep the immediate previous version unmodified.
@@ -79,17 +323,26 @@ impl<G: Grid> Elementry<G> {
             let mask = 1 << c;
 
             let is_alive = (self.rule & mask) != 0;
-            self.scratch.set(x as Index, 1, 1, is_alive);
+            self.scratch.set(x as Index, 0, 0, is_alive);
 
             count += (old != is_alive) as u32;
         }
 
+        self.finish_step();
+        count
+    }
+
+    /// Makes the generation just written to `scratch` the current one
+    fn finish_step(&mut self) {
         // Now that we're done stepping, we can swap our scrap and official cells.
         // Note: We expect Grids to be heap-allocated so this is quick enough.
         // TODO: We could bounce with indices instead. Might even be faster.
         core::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
 
-        count
+        if let Some(history) = &mut self.history {
+            history.push(self.generation, &self.cells);
+        }
     }
 
     /// Marks all cells as **dead**
@@ -101,6 +354,19 @@ impl<G: Grid> Elementry<G> {
     pub fn clear_alive(&mut self) {
         self.cells.fill(true);
     }
+
+    /// Marks all cells as **dead** and sets the [generation](Self::generation) back to `0`
+    ///
+    /// If stats or history are being recorded, they are emptied but recording continues.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generation = 0;
+        self.stats.clear();
+        if let Some(history) = &mut self.history {
+            history.clear();
+            history.push(self.generation, &self.cells);
+        }
+    }
 }
 
 impl Elementry<crate::BitGrid> {
@@ -111,6 +377,162 @@ impl Elementry<crate::BitGrid> {
             let rand_bytes = rng.next_u32().to_le_bytes();
             chunk.copy_from_slice(&rand_bytes[..chunk.len()]);
         }
+        self.cells.clear_padding();
+    }
+}
+
+/// Steps `cells` into `scratch` on packed words, returning the number of cells updated.
+///
+/// Each bit of a word is one cell. The row is shifted by one cell each way so that every cell lines up with
+/// its left and right neighbors, and `rule` is applied to all 64 cells of a word at once as a boolean function
+/// of the three words.
+fn step_words(cells: &BitGrid, scratch: &mut BitGrid, rule: u8, boundary: Boundary) -> u32 {
+    let width = cells.width() as usize;
+    let words_per_row = cells.words_per_row();
+    if words_per_row == 0 {
+        return 0;
+    }
+
+    // Valid bits in the last word of the row
+    let last_mask = match width % 64 {
+        0 => u64::MAX,
+        n => (1 << n) - 1,
+    };
+    let last_bit = (width - 1) % 64;
+
+    // Every bit of the rule spread across a word, for the neighborhoods from 0b000 to 0b111 as (left, center, right)
+    let table: [u64; 8] = core::array::from_fn(|pattern| match (rule >> pattern) & 1 {
+        0 => 0,
+        _ => u64::MAX,
+    });
+
+    let mut row = vec![0; words_per_row];
+    let (left_edge, right_edge) = cells.read_row_with_boundary(0, 0, boundary, &mut row);
+
+    let mut next = vec![0; words_per_row];
+    let mut count = 0;
+
+    for k in 0..words_per_row {
+        let center = row[k];
+
+        // Bit x of `left` is the cell at x - 1, and bit x of `right` is the cell at x + 1
+        let carry_in = if k == 0 {
+            left_edge as u64
+        } else {
+            row[k - 1] >> 63
+        };
+        let left = (center << 1) | carry_in;
+
+        let mut right = center >> 1;
+        if k + 1 < words_per_row {
+            right |= row[k + 1] << 63;
+        } else {
+            right |= (right_edge as u64) << last_bit;
+        }
+
+        // Pick each cell's bit of the rule by its neighborhood, one neighbor at a time
+        let mux = |select: u64, set: u64, unset: u64| (select & set) | (!select & unset);
+        let [t0, t1, t2, t3, t4, t5, t6, t7] = table;
+        let mut alive = mux(
+            left,
+            mux(center, mux(right, t7, t6), mux(right, t5, t4)),
+            mux(center, mux(right, t3, t2), mux(right, t1, t0)),
+        );
+        if k + 1 == words_per_row {
+            alive &= last_mask;
+        }
+
+        count += (alive ^ center).count_ones();
+        next[k] = alive;
+    }
+
+    scratch.write_row(0, 0, &next);
+    count
+}
+
+/// Moves a ring of packed cells `generations` steps forward under an additive rule that XORs `[left, center, right]`.
+///
+/// Reading cell `i` as the coefficient of `x^i`, a ring is a polynomial modulo `x^width - 1` over GF(2),
+/// and a step multiplies it by the rule's polynomial `left * x + center + right * x^-1`.
+/// Stepping `generations` times multiplies by that polynomial to the power of `generations`,
+/// which is built by repeated squaring. Squaring over GF(2) just doubles every exponent,
+/// so each power of two of the rule has at most 3 terms, and multiplying by it is at most 3 rotations of the ring.
+fn jump_words(words: &mut [u64], width: usize, terms: [bool; 3], mut generations: u64) {
+    if width == 0 {
+        return;
+    }
+
+    // Exponents of the rule raised to the current power of two. Terms that land on each other cancel out.
+    let toggle =
+        |power: &mut Vec<usize>, exponent: usize| match power.iter().position(|&e| e == exponent) {
+            Some(i) => {
+                power.swap_remove(i);
+            }
+            None => power.push(exponent),
+        };
+    let mut power = Vec::new();
+    for (is_term, exponent) in terms.into_iter().zip([1 % width, 0, width - 1]) {
+        if is_term {
+            toggle(&mut power, exponent);
+        }
+    }
+
+    let mut product = vec![0; words.len()];
+    let mut rotated = vec![0; words.len()];
+    while generations > 0 {
+        if generations & 1 != 0 {
+            product.fill(0);
+            for &exponent in &power {
+                rotate_words(words, width, exponent, &mut rotated);
+                for (product, rotated) in product.iter_mut().zip(&rotated) {
+                    *product ^= rotated;
+                }
+            }
+            words.copy_from_slice(&product);
+        }
+
+        let squared = power.clone();
+        power.clear();
+        for exponent in squared {
+            toggle(&mut power, 2 * exponent % width);
+        }
+        generations >>= 1;
+    }
+}
+
+/// Rotates a ring of `width` packed cells `by` cells to the right into `out`, so cell `i` moves to `i + by`
+fn rotate_words(words: &[u64], width: usize, by: usize, out: &mut [u64]) {
+    let len = words.len();
+    let word = |k: usize| words.get(k).copied().unwrap_or(0);
+
+    // Shift up by `by` for the cells that don't wrap, and down by `width - by` for the ones that do
+    let (up_words, up_bits) = (by / 64, by % 64);
+    let down = width - by;
+    let (down_words, down_bits) = (down / 64, down % 64);
+
+    for (k, out) in out.iter_mut().enumerate() {
+        let mut up = 0;
+        if k >= up_words {
+            up = word(k - up_words) << up_bits;
+            if up_bits > 0 && k > up_words {
+                up |= word(k - up_words - 1) >> (64 - up_bits);
+            }
+        }
+
+        let mut down = word(k + down_words) >> down_bits;
+        if down_bits > 0 {
+            down |= word(k + down_words + 1) << (64 - down_bits);
+        }
+
+        *out = up | down;
+    }
+
+    // Cells shifted past the end of the ring were already brought back around
+    if let Some(last) = out.get_mut(len - 1) {
+        *last &= match width % 64 {
+            0 => u64::MAX,
+            n => (1 << n) - 1,
+        };
     }
 }
 
@@ -128,6 +550,225 @@ impl Elementry {
 mod test {
     use super::*;
 
+    use rand::{rngs::SmallRng, SeedableRng};
+    use rstest::*;
+
+    #[rstest]
+    #[case::wrap(Boundary::Wrap, ".O.....O")]
+    #[case::dead(Boundary::Dead, ".O......")]
+    #[case::alive(Boundary::Alive, "OO.....O")]
+    #[case::reflect(Boundary::Reflect, "OO......")]
+    #[case::klein(Boundary::Klein, ".O.....O")]
+    fn check_rule_90_seed_on_edge(#[case] boundary: Boundary, #[case] expected: &str) {
+        let mut sim = Elementry::new(90, 8);
+        sim.set_boundary(boundary);
+        sim.set(0, true);
+
+        sim.step();
+        assert_eq!(sim.to_ascii(), expected);
+    }
+
+    #[test]
+    fn check_wider_than_i16() {
+        let width = 1_000_000;
+        let mut sim: Elementry = Elementry::new(90, width);
+        assert_eq!(sim.width(), width as Index);
+
+        // A seed on the far end spreads across the wrapping edge to the start of the row
+        sim.set(width as Index - 1, true);
+        sim.step();
+        assert!(sim.get(0));
+        assert!(sim.get(width as Index - 2));
+        assert_eq!(sim.cells().count(), width);
+        assert_eq!(sim.cells().filter(|&is_alive| is_alive).count(), 2);
+
+        sim.clear_random(&mut SmallRng::seed_from_u64(1));
+        assert!(sim.cells().skip(i16::MAX as usize).any(|is_alive| is_alive));
+    }
+
+    #[test]
+    fn check_step_with_stats() {
+        let mut sim: Elementry = Elementry::new(30, 16);
+        sim.set(8, true);
+        sim.record_stats(true);
+
+        // ........O....... -> .......OOO......
+        sim.step();
+        // .......OOO...... -> ......OO..O.....
+        sim.step();
+
+        let series = sim.time_series().unwrap();
+        assert_eq!(
+            series.as_slice(),
+            [
+                StepStats {
+                    generation: 1,
+                    births: 2,
+                    deaths: 0,
+                    population: 3,
+                    changed_bounds: Some((IVec3::new(7, 0, 0), IVec3::new(9, 0, 0))),
+                },
+                StepStats {
+                    generation: 2,
+                    births: 2,
+                    deaths: 2,
+                    population: 3,
+                    changed_bounds: Some((IVec3::new(6, 0, 0), IVec3::new(10, 0, 0))),
+                },
+            ]
+        );
+
+        sim.reset();
+        assert!(sim.time_series().unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case::rule_30_wrap(30, Boundary::Wrap, 200)]
+    #[case::rule_90_dead(90, Boundary::Dead, 64)]
+    #[case::rule_110_alive(110, Boundary::Alive, 129)]
+    #[case::rule_184_reflect(184, Boundary::Reflect, 65)]
+    #[case::rule_45_klein(45, Boundary::Klein, 100)]
+    #[case::rule_255(255, Boundary::Wrap, 70)]
+    #[case::rule_1(1, Boundary::Dead, 63)]
+    #[case::tiny(30, Boundary::Wrap, 1)]
+    #[case::narrow(150, Boundary::Wrap, 2)]
+    fn check_word_step_matches_generic(
+        #[case] rule: u8,
+        #[case] boundary: Boundary,
+        #[case] width: usize,
+    ) {
+        use rand::{rngs::SmallRng, SeedableRng};
+        let mut rng = SmallRng::seed_from_u64(0x5eed + rule as u64 * width as u64);
+
+        let mut fast: Elementry = Elementry::new(rule, width);
+        fast.set_boundary(boundary);
+        fast.clear_random(&mut rng);
+        let mut generic = fast.clone();
+
+        for generation in 0..64 {
+            let fast_count = fast.step();
+            let generic_count = generic.step_generic();
+
+            assert_eq!(
+                fast.grid(),
+                generic.grid(),
+                "Cells diverged in generation {generation}"
+            );
+            assert_eq!(
+                fast_count, generic_count,
+                "Update count diverged in generation {generation}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::rule_60(60, 100, 1000)]
+    #[case::rule_90(90, 65, 777)]
+    #[case::rule_102(102, 64, 300)]
+    #[case::rule_150(150, 200, 1234)]
+    #[case::rule_170(170, 63, 100)]
+    #[case::rule_240(240, 130, 129)]
+    #[case::rule_204(204, 10, 50)]
+    #[case::rule_0(0, 20, 3)]
+    #[case::one_cell(150, 1, 5)]
+    #[case::two_cells(90, 2, 7)]
+    #[case::three_cells(150, 3, 9)]
+    fn check_jump_matches_step(#[case] rule: u8, #[case] width: usize, #[case] generations: u64) {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        for seed in 0..4 {
+            let mut rng = SmallRng::seed_from_u64(seed + rule as u64 * width as u64);
+            let mut jumped: Elementry = Elementry::new(rule, width);
+            jumped.clear_random(&mut rng);
+            let mut stepped = jumped.clone();
+            assert!(jumped.can_jump());
+
+            // Jump in uneven pieces, checking along the way
+            let mut done = 0;
+            for piece in [1, 2, 5, generations] {
+                let piece = piece.min(generations - done);
+                jumped.jump(piece);
+                for _ in 0..piece {
+                    stepped.step();
+                }
+                done += piece;
+
+                assert_eq!(
+                    jumped.grid(),
+                    stepped.grid(),
+                    "Seed {seed}, generation {done}"
+                );
+                assert_eq!(jumped.generation(), stepped.generation());
+            }
+        }
+    }
+
+    #[test]
+    fn check_jump_far_ahead() {
+        use rand::{rngs::SmallRng, SeedableRng};
+        let mut rng = SmallRng::seed_from_u64(0x5eed);
+
+        // Rule 170 shifts every cell one to the left, so a jump is a rotation by the generation count
+        let mut shift: Elementry = Elementry::new(170, 100);
+        shift.clear_random(&mut rng);
+        let before: Vec<bool> = shift.cells().collect();
+        let generations = 1 << 40;
+        shift.jump(generations);
+        let after: Vec<bool> = shift.cells().collect();
+        for x in 0..100 {
+            assert_eq!(after[x], before[(x + (generations % 100) as usize) % 100]);
+        }
+
+        // Rule 60 on a ring as wide as a power of two empties within that many generations
+        let mut rule_60: Elementry = Elementry::new(60, 128);
+        rule_60.clear_random(&mut rng);
+        rule_60.jump(u64::MAX);
+        assert_eq!(rule_60.cells().filter(|&is_alive| is_alive).count(), 0);
+        assert_eq!(rule_60.generation(), u64::MAX);
+    }
+
+    #[test]
+    fn check_jump_on_any_grid() {
+        let mut packed: Elementry<PackedGrid<bool>> = Elementry::new(150, 70);
+        let mut bits: Elementry = Elementry::new(150, 70);
+        for x in [0, 3, 35, 69] {
+            packed.set(x, true);
+            bits.set(x, true);
+        }
+
+        packed.jump(12_345);
+        bits.jump(12_345);
+        assert!(packed.cells().eq(bits.cells()));
+    }
+
+    #[test]
+    fn check_jump_falls_back_to_steps() {
+        let mut rule_30: Elementry = Elementry::new(30, 32);
+        rule_30.set(16, true);
+        assert!(!rule_30.can_jump());
+
+        let mut rule_90: Elementry = Elementry::new(90, 32);
+        rule_90.set(16, true);
+        rule_90.set_boundary(Boundary::Dead);
+        assert!(!rule_90.can_jump());
+
+        for mut sim in [rule_30, rule_90] {
+            let mut stepped = sim.clone();
+            sim.jump(40);
+            for _ in 0..40 {
+                stepped.step();
+            }
+            assert_eq!(sim.grid(), stepped.grid());
+        }
+
+        // Recording needs every generation
+        let mut recorded: Elementry = Elementry::new(90, 32);
+        recorded.record_history(Some(8));
+        assert!(!recorded.can_jump());
+        recorded.jump(20);
+        assert_eq!(recorded.history().unwrap().first_generation(), Some(13));
+    }
+
     #[test]
     #[rustfmt::skip]
     fn check_single_seed() {
@@ -0,0 +1,443 @@
+use crate::prelude::*;
+use crate::GenerationsRule;
+
+use alloc::string::{String, ToString};
+
+/// A simulation of a [`GenerationsRule`], such as Brian's Brain or Star Wars.
+///
+/// This works like [`Life`](crate::Life), except cells hold a `u8` state instead of a `bool`:
+/// `0` is dead, `1` is alive, and everything above is dying. See [`GenerationsRule`] for how states change.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Generations, GenerationsRule};
+/// let mut brain = Generations::new(GenerationsRule::BRIANS_BRAIN, 8, 8);
+/// brain.set(3, 3, 1);
+/// brain.set(4, 3, 1);
+///
+/// // Both cells start dying, and the two cells above and below them are born
+/// brain.step();
+/// assert_eq!(brain.get(3, 3), 2);
+/// assert_eq!(brain.population(), 4);
+/// ```
+#[derive(Clone)]
+pub struct Generations<G: Grid<u8> = PackedGrid<u8>> {
+    /// Current state of the simulation
+    cells: G,
+
+    /// Scratch copy of cells used when stepping the simulation
+    scratch: G,
+
+    /// How cells beyond the edges of `cells` are treated
+    boundary: Boundary,
+
+    /// Which cells are born and survive each step, and how long dying cells take to decay
+    rule: GenerationsRule,
+
+    /// Number of steps taken since creation or the last reset
+    generation: u64,
+}
+
+/// Basic Usage
+impl Generations<PackedGrid<u8>> {
+    /// Creates a new simulation with the given rule and dimensions where all cells are initially **dead**.
+    ///
+    /// Cells are packed into the fewest bits that can hold every state of `rule`.
+    pub fn new(rule: GenerationsRule, width: usize, height: usize) -> Self {
+        let bits = match rule.states() {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
+        Self::new_with_cells(rule, PackedGrid::new_with_bits(width, height, 1, bits))
+    }
+
+    /// Creates a new simulation from a pattern in the RLE format. See [`Rle`](crate::Rle) for details.
+    ///
+    /// Dying states are written as `B`, `C`, etc., like Golly does. The rule comes from the header,
+    /// or is Brian's Brain if it has none. Returns `None` if the pattern or its rule can't be parsed,
+    /// or if it uses more states than its rule has.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{Generations, GenerationsRule};
+    /// let brain = Generations::from_rle("x = 3, y = 1, rule = B2/S/C3\nABA!").unwrap();
+    /// assert_eq!(brain.rule(), GenerationsRule::BRIANS_BRAIN);
+    /// assert_eq!(brain.get(1, 0), 2);
+    /// ```
+    pub fn from_rle(text: &str) -> Option<Self> {
+        let (header, grid) = crate::rle::parse_states(text)?;
+        let rule = match &header.rule {
+            Some(rule) => GenerationsRule::parse(rule)?,
+            None => GenerationsRule::BRIANS_BRAIN,
+        };
+        if grid.iter().any(|(_, state)| state >= rule.states()) {
+            return None;
+        }
+
+        let mut generations = Self::new(rule, header.width, header.height);
+        for (pos, state) in grid.iter() {
+            generations.cells.set(pos.x, pos.y, 0, state);
+        }
+        Some(generations)
+    }
+
+    /// Writes the current cells and rule in the RLE format
+    pub fn to_rle(&self) -> String {
+        crate::rle::write_states(&self.cells, Some(self.rule.to_string()))
+    }
+}
+
+impl<G: Grid<u8> + Clone> Generations<G> {
+    /// Creates a new simulation with the given rule and existing cells
+    ///
+    /// The simulation uses the [`Boundary`] of `cells`. See [`set_boundary`](Self::set_boundary) to override it.
+    pub fn new_with_cells(rule: GenerationsRule, cells: G) -> Self {
+        let scratch = cells.clone();
+        let boundary = cells.boundary();
+        Self {
+            cells,
+            scratch,
+            boundary,
+            rule,
+            generation: 0,
+        }
+    }
+}
+
+impl<G: Grid<u8>> Generations<G> {
+    /// The width of the simulation
+    pub fn width(&self) -> i16 {
+        self.cells.width() as i16
+    }
+
+    /// The height of the simulation
+    pub fn height(&self) -> i16 {
+        self.cells.height() as i16
+    }
+
+    /// How many generations have been stepped through since this simulation was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The rule this simulation steps under
+    pub fn rule(&self) -> GenerationsRule {
+        self.rule
+    }
+
+    /// Changes the rule used by future steps.
+    ///
+    /// Existing cells are kept as-is, even if they are past the last state of `rule`. They decay to dead on the next step.
+    pub fn set_rule(&mut self, rule: GenerationsRule) {
+        self.rule = rule;
+    }
+
+    /// How cells beyond the edges of the simulation are treated
+    ///
+    /// [`Boundary::Alive`] surrounds the simulation with cells in state `1`.
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how cells beyond the edges of the simulation are treated.
+    ///
+    /// This overrides the [`Boundary`] of the underlying grid for this simulation only.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    /// Checks the state of the cell at `(x, y)`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    #[track_caller]
+    pub fn get(&self, x: i16, y: i16) -> u8 {
+        self.cells
+            .get_with_boundary(x as Index, y as Index, 0, self.boundary)
+    }
+
+    /// Sets the state of the cell at `(x, y)`, returning its previous state.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells outside of a bounded simulation are ignored.
+    #[track_caller]
+    pub fn set(&mut self, x: i16, y: i16, state: u8) -> u8 {
+        self.cells
+            .set_with_boundary(x as Index, y as Index, 0, state, self.boundary)
+    }
+
+    /// Number of **alive** cells, not counting dying ones
+    pub fn population(&self) -> usize {
+        let (min, max) = self.cells.extent();
+        (min.y..max.y)
+            .flat_map(|y| (min.x..max.x).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.cells.get(x, y, 0) == 1)
+            .count()
+    }
+
+    pub fn cells(&self) -> &G {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut G {
+        &mut self.cells
+    }
+
+    pub fn into_cells(self) -> G {
+        self.cells
+    }
+
+    /// Steps the simulation once, returning the number of cells updated
+    ///
+    /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
+    pub fn step(&mut self) -> u32 {
+        let mut count = 0;
+
+        let (min, max) = self.cells.extent();
+        let alive =
+            |x: Index, y: Index| (self.cells.get_with_boundary(x, y, 0, self.boundary) == 1) as u8;
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let mut live_count = 0;
+
+                live_count += alive(x - 1, y - 1);
+                live_count += alive(x - 1, y + 0);
+                live_count += alive(x - 1, y + 1);
+
+                live_count += alive(x + 0, y - 1);
+                // Don't count itself, skip (x+0, y+0)
+                live_count += alive(x + 0, y + 1);
+
+                live_count += alive(x + 1, y - 1);
+                live_count += alive(x + 1, y + 0);
+                live_count += alive(x + 1, y + 1);
+
+                let was = self.cells.get(x, y, 0);
+                let is = self.rule.next_state(was, live_count);
+
+                self.scratch.set(x, y, 0, is);
+
+                if was != is {
+                    count += 1;
+                }
+            }
+        }
+
+        core::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
+
+        count
+    }
+
+    /// Marks all cells as **dead**
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Marks all cells as **dead** and sets the [generation](Self::generation) back to `0`
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generation = 0;
+    }
+}
+
+/// Local typedef to simplify using image's image type. We won't be changing the backing store from a Vec.
+#[cfg(feature = "image")]
+type ImageBuffer<P> = image::ImageBuffer<P, alloc::vec::Vec<<P as image::Pixel>::Subpixel>>;
+
+#[cfg(feature = "image")]
+impl<G: Grid<u8>> Generations<G> {
+    /// Builds a palette with one color per state of this simulation's rule, for [`to_image`](Self::to_image).
+    ///
+    /// Dead cells are `dead` and alive cells are `alive`. Dying cells fade evenly from `alive` towards `faded`,
+    /// with the oldest dying state being `faded` itself.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{Generations, GenerationsRule};
+    /// # use image::Rgb;
+    /// let star_wars = Generations::new(GenerationsRule::STAR_WARS, 4, 4);
+    /// let palette = star_wars.gradient_palette(Rgb([0, 0, 0]), Rgb([255, 255, 255]), Rgb([0, 0, 255]));
+    /// assert_eq!(
+    ///     palette,
+    ///     [Rgb([0, 0, 0]), Rgb([255, 255, 255]), Rgb([128, 128, 255]), Rgb([0, 0, 255])]
+    /// );
+    /// ```
+    pub fn gradient_palette(
+        &self,
+        dead: image::Rgb<u8>,
+        alive: image::Rgb<u8>,
+        faded: image::Rgb<u8>,
+    ) -> alloc::vec::Vec<image::Rgb<u8>> {
+        let dying = self.rule.states() as u32 - 2;
+
+        let mut palette = alloc::vec![dead, alive];
+        for i in 1..=dying {
+            let mix = |a: u8, b: u8| {
+                let (a, b) = (a as u32, b as u32);
+                // Round to nearest
+                ((a * (dying - i) + b * i + dying / 2) / dying) as u8
+            };
+            palette.push(image::Rgb(core::array::from_fn(|c| {
+                mix(alive.0[c], faded.0[c])
+            })));
+        }
+        palette
+    }
+
+    /// Convert the cells into an [`image::ImageBuffer`](image::ImageBuffer).
+    ///
+    /// # Coloring
+    /// Cells in state `n` are colored with `palette[n]`. States past the end of `palette` use its last color.
+    /// See [`gradient_palette`](Self::gradient_palette) for a palette that fades out dying cells.
+    ///
+    /// # Panics
+    /// If `palette` is empty.
+    pub fn to_image<P>(&self, palette: &[P]) -> ImageBuffer<P>
+    where
+        P: image::Pixel,
+    {
+        assert!(!palette.is_empty(), "The palette needs at least one color");
+
+        ImageBuffer::<P>::from_fn(
+            self.cells.width() as u32,
+            self.cells.height() as u32,
+            |x: u32, y: u32| {
+                let state = self.cells.get(x as _, y as _, 0) as usize;
+                palette[state.min(palette.len() - 1)]
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use rstest::*;
+
+    fn states(generations: &Generations) -> String {
+        let mut text = String::new();
+        for y in 0..generations.height() {
+            for x in 0..generations.width() {
+                text.push(char::from(b'0' + generations.get(x, y)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn check_lone_cell_decays() {
+        let mut star_wars = Generations::new(GenerationsRule::STAR_WARS, 3, 3);
+        star_wars.set(1, 1, 1);
+
+        let mut history = [0; 4];
+        for state in &mut history {
+            *state = star_wars.get(1, 1);
+            star_wars.step();
+        }
+
+        assert_eq!(history, [1, 2, 3, 0]);
+        assert_eq!(star_wars.step(), 0);
+    }
+
+    #[test]
+    fn check_brians_brain_domino() {
+        let mut brain = Generations::new(GenerationsRule::BRIANS_BRAIN, 6, 5);
+        brain.set(2, 2, 1);
+        brain.set(3, 2, 1);
+
+        assert_eq!(brain.step(), 6);
+        assert_eq!(
+            states(&brain),
+            indoc::indoc! {"
+                000000
+                001100
+                002200
+                001100
+                000000
+            "}
+        );
+
+        // Dying cells aren't alive neighbors, and can't be born until they're dead
+        brain.step();
+        assert_eq!(
+            states(&brain),
+            indoc::indoc! {"
+                001100
+                002200
+                010010
+                002200
+                001100
+            "}
+        );
+    }
+
+    #[rstest]
+    #[case::conway(LifeRule::CONWAY)]
+    #[case::highlife(LifeRule::HIGHLIFE)]
+    #[case::b0(LifeRule::parse("B0/S8").unwrap())]
+    fn check_two_states_matches_life(#[case] rule: LifeRule) {
+        let mut rng = SmallRng::seed_from_u64(9);
+        let bits = BitGrid::new_with_fn(19, 11, 1, |_, _, _| rng.random());
+
+        let mut life: crate::Life = crate::Life::new_with_cells_and_rule(rule, bits.clone());
+        let mut generations =
+            Generations::new_with_cells(rule.into(), PackedGrid::from_bitgrid(&bits, 1));
+
+        for _ in 0..12 {
+            assert_eq!(generations.step(), life.step());
+            assert_eq!(generations.cells().to_bitgrid(|s| s == 1), *life.cells());
+        }
+    }
+
+    #[test]
+    fn check_rle_round_trip() {
+        let text = "x = 4, y = 2, rule = B2/S345/C4\nABC$.2A!\n";
+        let star_wars = Generations::from_rle(text).unwrap();
+
+        assert_eq!(star_wars.rule(), GenerationsRule::STAR_WARS);
+        assert_eq!(star_wars.cells().bits_per_cell(), 2);
+        assert_eq!(star_wars.population(), 3);
+        assert_eq!(star_wars.to_rle(), text);
+
+        // State 4 doesn't exist in a 4 state rule
+        assert!(Generations::from_rle("x = 1, y = 1, rule = B2/S345/C4\nD!").is_none());
+    }
+
+    #[test]
+    fn check_rle_wider_than_i16() {
+        let text = "x = 40000, y = 1, rule = B2/S/C3\n39998.BA!\n";
+        let brain = Generations::from_rle(text).unwrap();
+
+        assert_eq!(brain.cells().get(39_998, 0, 0), 2);
+        assert_eq!(brain.cells().get(39_999, 0, 0), 1);
+        assert_eq!(brain.population(), 1);
+        assert_eq!(brain.to_rle(), text);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_to_image() {
+        use image::Rgb;
+
+        let mut brain = Generations::new(GenerationsRule::BRIANS_BRAIN, 3, 1);
+        brain.set(0, 0, 1);
+        brain.set(1, 0, 2);
+
+        let palette =
+            brain.gradient_palette(Rgb([0, 0, 0]), Rgb([255, 255, 255]), Rgb([255, 0, 0]));
+        assert_eq!(palette.len(), 3);
+
+        let image = brain.to_image(&palette);
+        assert_eq!(
+            image.pixels().copied().collect::<alloc::vec::Vec<_>>(),
+            [Rgb([255, 255, 255]), Rgb([255, 0, 0]), Rgb([0, 0, 0])]
+        );
+    }
+}
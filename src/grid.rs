@@ -7,10 +7,13 @@ pub type Index = i32;
 /// A `Grid` has a width and height which are fixed at construction.
 /// Cells in a grid can be set or unset individually (with [`set`](Grid::set)) or in bulk with [`fill()`](Grid::fill).
 ///
+/// Cells are `bool`s by default, but a grid can hold any small [`Cell`] type (such as `u8` or an enum) for automata
+/// with more than two states. [`PackedGrid`] stores those at 2, 4, or 8 bits per cell.
+///
 /// Many methods on `Grid` have provided implementations that are correct and good enough, but your specific grid may
 /// be able to implement them smarter. For example, [`BitGrid`](crate::BitGrid) stores its cells as a bit vector,
 /// contiguous in memory. As such, [`BitGird::fill`](crate::BitGrid::fill) is implemented using `fill` method on `core::slice`.
-pub trait Grid: Sized {
+pub trait Grid<C: Cell = bool>: Sized {
     // Checking size
     fn width(&self) -> Index {
         self.dims().x
@@ -23,28 +26,173 @@ pub trait Grid: Sized {
     }
     fn dims(&self) -> IVec3;
 
-    fn resize(&mut self, new_dims: IVec3);
+    /// Resizes the grid to `new_dims`, keeping existing cells at their current coordinates.
+    ///
+    /// This is shorthand for [`resize_with_anchor`](Grid::resize_with_anchor) with [`Anchor::TopLeft`].
+    fn resize(&mut self, new_dims: IVec3) {
+        self.resize_with_anchor(new_dims, Anchor::TopLeft);
+    }
+
+    /// Resizes the grid to `new_dims`, placing existing cells according to `anchor`.
+    ///
+    /// Cells that land outside of the new dimensions are dropped, and newly exposed cells are unset.
+    fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor);
+
+    /// The region holding every cell of this grid, as `(min, max)` with `max` exclusive.
+    ///
+    /// Fixed-size grids span `(0, dims)`. [Unbounded](Grid::is_unbounded) grids return the bounding box of their set cells.
+    fn extent(&self) -> (IVec3, IVec3) {
+        (IVec3::zero(), self.dims())
+    }
+
+    /// Whether this grid extends forever in every direction, like [`SparseGrid`].
+    ///
+    /// Unbounded grids don't wrap and ignore any [`Boundary`]. Their [`dims`](Grid::dims) are the size of their [`extent`](Grid::extent).
+    fn is_unbounded(&self) -> bool {
+        false
+    }
+
+    /// How this grid treats coordinates outside of its dimensions.
+    ///
+    /// Grids that don't track this themselves wrap around.
+    fn boundary(&self) -> Boundary {
+        Boundary::Wrap
+    }
 
     // Indexed access
     #[track_caller]
-    fn get(&self, x: Index, y: Index, z: Index) -> bool;
+    fn get(&self, x: Index, y: Index, z: Index) -> C;
+
+    /// Checks the cell at `(x, y, z)`, resolving out of bounds coordinates with `boundary` instead of [`Grid::boundary`].
+    #[track_caller]
+    fn get_with_boundary(&self, x: Index, y: Index, z: Index, boundary: Boundary) -> C {
+        match boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => self.get(pos.x, pos.y, pos.z),
+            Resolved::Constant(elem) => C::from_alive(elem),
+        }
+    }
+
+    #[track_caller]
+    fn set(&mut self, x: Index, y: Index, z: Index, elem: C) -> C;
 
+    /// Sets the cell at `(x, y, z)`, resolving out of bounds coordinates with `boundary` instead of [`Grid::boundary`].
+    ///
+    /// Writes to cells outside of the grid entirely are ignored, and return the boundary's value.
     #[track_caller]
-    fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool;
+    fn set_with_boundary(
+        &mut self,
+        x: Index,
+        y: Index,
+        z: Index,
+        elem: C,
+        boundary: Boundary,
+    ) -> C {
+        match boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => self.set(pos.x, pos.y, pos.z, elem),
+            Resolved::Constant(old) => C::from_alive(old),
+        }
+    }
 
     #[track_caller]
-    fn flip(&mut self, x: Index, y: Index, z: Index) -> bool {
+    fn flip(&mut self, x: Index, y: Index, z: Index) -> C
+    where
+        C: core::ops::Not<Output = C>,
+    {
         let old = self.get(x, y, z);
         self.set(x, y, z, !old);
         old
     }
 
+    /// Downcasts to a [`BitGrid`], which lets simulations take faster word-level paths.
+    ///
+    /// Grids that aren't a `BitGrid` (or don't want to expose one) should leave this as `None`.
+    fn as_bitgrid(&self) -> Option<&crate::BitGrid> {
+        None
+    }
+
+    /// Mutable version of [`as_bitgrid`](Grid::as_bitgrid)
+    fn as_bitgrid_mut(&mut self) -> Option<&mut crate::BitGrid> {
+        None
+    }
+
+    /// Downcasts to a [`SparseGrid`], which lets simulations only visit cells near set cells.
+    #[cfg(feature = "std")]
+    fn as_sparse(&self) -> Option<&crate::SparseGrid> {
+        None
+    }
+
+    /// Mutable version of [`as_sparse`](Grid::as_sparse)
+    #[cfg(feature = "std")]
+    fn as_sparse_mut(&mut self) -> Option<&mut crate::SparseGrid> {
+        None
+    }
+
+    // Patterns
+
+    /// Copies the set cells of `pattern` onto this grid, with the pattern's origin at `at`.
+    ///
+    /// The pattern is first transformed by `transform` (in the `x`/`y` plane) and placed so that its transformed
+    /// bounding box still starts at `at`. Its set cells are [`Blend::Or`]ed in, and cells outside this grid are
+    /// resolved with [`boundary()`](Self::boundary). See [`stamp_with`](Self::stamp_with) for more control.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::prelude::*;
+    /// let glider = BitGrid::parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    ///
+    /// // A glider flying up and left instead of down and right
+    /// let mut grid = BitGrid::new(8, 8, 1);
+    /// grid.stamp(&glider, IVec3::new(2, 2, 0), Transform::Rotate180);
+    /// assert_eq!(grid.count_set(), 5);
+    /// assert!(grid.get(2, 2, 0) && grid.get(3, 2, 0) && grid.get(4, 2, 0));
+    /// ```
+    fn stamp(&mut self, pattern: &crate::BitGrid, at: IVec3, transform: Transform) {
+        let boundary = self.boundary();
+        self.stamp_with(pattern, at, transform, Blend::Or, boundary);
+    }
+
+    /// Copies `pattern` onto this grid like [`stamp`](Self::stamp), with a choice of how cells are combined
+    /// and what happens to pattern cells that land outside of this grid.
+    ///
+    /// Pattern cells are resolved with `edges` like any other out of bounds access: [`Boundary::Dead`] clips them
+    /// and [`Boundary::Wrap`] wraps them around to the opposite edge.
+    /// Grids with more than two states use [`Cell::ALIVE`] for set cells and [`Cell::DEAD`] for unset ones.
+    fn stamp_with(
+        &mut self,
+        pattern: &crate::BitGrid,
+        at: IVec3,
+        transform: Transform,
+        blend: Blend,
+        edges: Boundary,
+    ) {
+        let dims = pattern.dims();
+        for z in 0..dims.z {
+            for y in 0..dims.y {
+                for x in 0..dims.x {
+                    let is_set = pattern.get(x, y, z);
+                    let pos = at + transform.apply(IVec3::new(x, y, z), dims);
+
+                    let elem = match blend {
+                        Blend::Or if is_set => C::ALIVE,
+                        Blend::Xor if is_set => {
+                            let old = self.get_with_boundary(pos.x, pos.y, pos.z, edges);
+                            C::from_alive(old == C::DEAD)
+                        }
+                        Blend::Overwrite => C::from_alive(is_set),
+                        Blend::Or | Blend::Xor => continue,
+                    };
+                    self.set_with_boundary(pos.x, pos.y, pos.z, elem, edges);
+                }
+            }
+        }
+    }
+
     // Misc
     fn clear(&mut self) {
-        self.fill(false);
+        self.fill(C::DEAD);
     }
 
-    fn fill(&mut self, set: bool) {
+    fn fill(&mut self, set: C) {
         for z in 0..self.depth() {
             for y in 0..self.height() {
                 for x in 0..self.width() {
@@ -55,7 +203,385 @@ pub trait Grid: Sized {
     }
 }
 
-pub trait GridNew: Grid {
+/// The state of a single cell in a [`Grid`].
+///
+/// Cells are small, plain values that can be packed into a few bits, such as `bool`, `u8`, or a fieldless enum.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// enum Traffic {
+///     #[default]
+///     Empty,
+///     Car,
+///     Truck,
+/// }
+///
+/// impl Cell for Traffic {
+///     const BITS: u8 = 2;
+///     const DEAD: Self = Traffic::Empty;
+///     const ALIVE: Self = Traffic::Car;
+///
+///     fn to_bits(self) -> u8 {
+///         self as u8
+///     }
+///
+///     fn from_bits(bits: u8) -> Self {
+///         match bits {
+///             1 => Traffic::Car,
+///             2 => Traffic::Truck,
+///             _ => Traffic::Empty,
+///         }
+///     }
+/// }
+///
+/// let mut road: PackedGrid<Traffic> = PackedGrid::new(16, 1, 1);
+/// road.set(3, 0, 0, Traffic::Truck);
+/// assert_eq!(road.get(3, 0, 0), Traffic::Truck);
+/// assert_eq!(road.bits_per_cell(), 2);
+/// ```
+pub trait Cell: Copy + Eq + Default + core::fmt::Debug {
+    /// The fewest bits that can hold every state of this cell. Must be at most 8.
+    const BITS: u8;
+
+    /// The state of an empty cell, and of cells outside a [`Boundary::Dead`] grid.
+    const DEAD: Self;
+
+    /// The state of cells outside a [`Boundary::Alive`] grid.
+    const ALIVE: Self;
+
+    /// Packs this cell into the low [`BITS`](Cell::BITS) bits of a byte
+    fn to_bits(self) -> u8;
+
+    /// Unpacks a cell from the low [`BITS`](Cell::BITS) bits of a byte. Invalid patterns should map to some valid state.
+    fn from_bits(bits: u8) -> Self;
+
+    /// Either [`ALIVE`](Cell::ALIVE) or [`DEAD`](Cell::DEAD)
+    fn from_alive(is_alive: bool) -> Self {
+        if is_alive {
+            Self::ALIVE
+        } else {
+            Self::DEAD
+        }
+    }
+}
+
+impl Cell for bool {
+    const BITS: u8 = 1;
+    const DEAD: Self = false;
+    const ALIVE: Self = true;
+
+    fn to_bits(self) -> u8 {
+        self as u8
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        (bits & 1) != 0
+    }
+}
+
+impl Cell for u8 {
+    const BITS: u8 = 8;
+    const DEAD: Self = 0;
+    const ALIVE: Self = 1;
+
+    fn to_bits(self) -> u8 {
+        self
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        bits
+    }
+}
+
+/// Flat index of the in-bounds cell at `pos` in a grid of size `dims`, stored x-major then y then z.
+pub(crate) fn flat_index(dims: IVec3, pos: IVec3) -> usize {
+    debug_assert!(
+        (0..dims.x).contains(&pos.x)
+            && (0..dims.y).contains(&pos.y)
+            && (0..dims.z).contains(&pos.z),
+        "{pos:?} is out of bounds of {dims:?}"
+    );
+
+    pos.x as usize + pos.y as usize * dims.x as usize + pos.z as usize * (dims.x * dims.y) as usize
+}
+
+/// Moves the cells of `grid` into a blank grid of `new_dims` built by `blank`, as [`Grid::resize_with_anchor`] describes.
+///
+/// Only the region that overlaps both grids is visited, and only cells other than [`C::DEAD`](Cell::DEAD) are written.
+pub(crate) fn resize_into<C: Cell, G: Grid<C>>(
+    grid: &mut G,
+    new_dims: IVec3,
+    anchor: Anchor,
+    blank: impl FnOnce(IVec3) -> G,
+) {
+    let new_dims = new_dims.max_by_component(IVec3::zero());
+    let offset = anchor.offset(grid.dims(), new_dims);
+    if new_dims == grid.dims() && offset == IVec3::zero() {
+        return;
+    }
+
+    let mut resized = blank(new_dims);
+
+    // Only walk the region that overlaps in both grids, in old coordinates.
+    let start = (-offset).max_by_component(IVec3::zero());
+    let end = (new_dims - offset).min_by_component(grid.dims());
+
+    for z in start.z..end.z {
+        for y in start.y..end.y {
+            for x in start.x..end.x {
+                let cell = grid.get(x, y, z);
+                if cell != C::DEAD {
+                    resized.set(x + offset.x, y + offset.y, z + offset.z, cell);
+                }
+            }
+        }
+    }
+
+    *grid = resized;
+}
+
+/// One of the 8 symmetries of a square, for turning and mirroring patterns in the `x`/`y` plane.
+///
+/// Rotations are clockwise, as seen with `y` pointing down the screen. The `z` axis is left as-is.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// // In a 3x2 grid, the top right corner rotates to the bottom right corner of a 2x3 grid
+/// let dims = IVec3::new(3, 2, 1);
+/// assert_eq!(Transform::Rotate90.apply(IVec3::new(2, 0, 0), dims), IVec3::new(1, 2, 0));
+/// assert_eq!(Transform::Rotate90.dims(dims), IVec3::new(2, 3, 1));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+
+    /// Mirrors left and right
+    FlipX,
+
+    /// Mirrors top and bottom
+    FlipY,
+
+    /// Swaps `x` and `y`, mirroring along the diagonal through the origin
+    Transpose,
+
+    /// Mirrors along the other diagonal
+    AntiTranspose,
+}
+
+impl Transform {
+    /// Every symmetry, starting with [`Identity`](Self::Identity)
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipX,
+        Self::FlipY,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    /// Whether this swaps the `x` and `y` axes, which also swaps the width and height of a grid
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Self::Rotate90 | Self::Rotate270 | Self::Transpose | Self::AntiTranspose
+        )
+    }
+
+    /// The dimensions of a grid of `dims` after this transform
+    pub fn dims(self, dims: IVec3) -> IVec3 {
+        if self.swaps_axes() {
+            IVec3::new(dims.y, dims.x, dims.z)
+        } else {
+            dims
+        }
+    }
+
+    /// Where the cell at `pos` of a grid of `dims` ends up, in the transformed grid of [`self.dims(dims)`](Self::dims)
+    pub fn apply(self, pos: IVec3, dims: IVec3) -> IVec3 {
+        let (x, y, z) = (pos.x, pos.y, pos.z);
+        let (w, h) = (dims.x - 1, dims.y - 1);
+        let (x, y) = match self {
+            Self::Identity => (x, y),
+            Self::Rotate90 => (h - y, x),
+            Self::Rotate180 => (w - x, h - y),
+            Self::Rotate270 => (y, w - x),
+            Self::FlipX => (w - x, y),
+            Self::FlipY => (x, h - y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (h - y, w - x),
+        };
+        IVec3::new(x, y, z)
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            other => other,
+        }
+    }
+}
+
+/// How [`Grid::stamp_with`] combines a pattern with the cells already in a grid
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Blend {
+    /// Set cells of the pattern are set, and everything else is left alone
+    #[default]
+    Or,
+
+    /// Set cells of the pattern flip the cell underneath them
+    Xor,
+
+    /// Every cell under the pattern, set or not, is replaced
+    Overwrite,
+}
+
+/// Controls where the existing cells of a [`Grid`] end up when it is resized.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// Cells keep their coordinates. Growing or shrinking happens on the far edges of each axis.
+    #[default]
+    TopLeft,
+
+    /// Cells stay centered. Growing or shrinking is split between both edges of each axis.
+    ///
+    /// When the change along an axis is odd, the extra cell goes on the far edge.
+    Center,
+
+    /// The cell at the origin moves to this position in the resized grid, and every other cell moves with it.
+    ///
+    /// Negative components are allowed and drop the leading cells of that axis.
+    Offset(IVec3),
+}
+
+impl Anchor {
+    /// How far each cell moves when a grid of `old_dims` is resized to `new_dims`
+    pub fn offset(self, old_dims: IVec3, new_dims: IVec3) -> IVec3 {
+        match self {
+            Anchor::TopLeft => IVec3::zero(),
+            Anchor::Center => (new_dims - old_dims) / 2,
+            Anchor::Offset(offset) => offset,
+        }
+    }
+}
+
+/// Controls how coordinates outside of a [`Grid`]'s dimensions are treated.
+///
+/// The edges of each axis are handled independently, so a 2D grid with [`Boundary::Dead`] is a bounded box,
+/// while [`Boundary::Wrap`] turns it into a torus.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Boundary {
+    /// Coordinates wrap around to the opposite edge of each axis.
+    #[default]
+    Wrap,
+
+    /// Cells outside of the grid are always **dead**. Writes to them are ignored.
+    Dead,
+
+    /// Cells outside of the grid are always **alive**. Writes to them are ignored.
+    Alive,
+
+    /// Coordinates mirror back into the grid, as if the edge was reflected.
+    ///
+    /// The first cell outside an edge reads the edge cell, the next reads its neighbor, and so on.
+    Reflect,
+
+    /// Coordinates wrap like [`Boundary::Wrap`], but crossing the top or bottom edge (`y`) also mirrors `x`.
+    ///
+    /// In 2D this makes the grid a Klein bottle (or twisted torus).
+    Klein,
+}
+
+/// Where a coordinate reads its cell from after applying a [`Boundary`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    /// The coordinate maps onto this in-bounds cell
+    Cell(IVec3),
+
+    /// The coordinate is outside the grid and always holds this value
+    Constant(bool),
+}
+
+impl Boundary {
+    /// Maps `pos` into a grid of size `dims` according to this boundary.
+    ///
+    /// In-bounds coordinates always resolve to themselves.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::prelude::*;
+    /// # use simulations::grid::Resolved;
+    /// let dims = IVec3::new(4, 4, 1);
+    /// let pos = IVec3::new(-1, 2, 0);
+    ///
+    /// assert_eq!(Boundary::Wrap.resolve(dims, pos), Resolved::Cell(IVec3::new(3, 2, 0)));
+    /// assert_eq!(Boundary::Reflect.resolve(dims, pos), Resolved::Cell(IVec3::new(0, 2, 0)));
+    /// assert_eq!(Boundary::Dead.resolve(dims, pos), Resolved::Constant(false));
+    /// ```
+    pub fn resolve(self, dims: IVec3, pos: IVec3) -> Resolved {
+        let in_bounds = |i: Index, n: Index| (0..n).contains(&i);
+        if in_bounds(pos.x, dims.x) && in_bounds(pos.y, dims.y) && in_bounds(pos.z, dims.z) {
+            return Resolved::Cell(pos);
+        }
+
+        // Nothing to map onto, so every coordinate is out of bounds
+        if dims.x <= 0 || dims.y <= 0 || dims.z <= 0 {
+            return Resolved::Constant(self == Boundary::Alive);
+        }
+
+        match self {
+            Boundary::Wrap => Resolved::Cell(IVec3::new(
+                pos.x.rem_euclid(dims.x),
+                pos.y.rem_euclid(dims.y),
+                pos.z.rem_euclid(dims.z),
+            )),
+            Boundary::Dead => Resolved::Constant(false),
+            Boundary::Alive => Resolved::Constant(true),
+            Boundary::Reflect => Resolved::Cell(IVec3::new(
+                reflect(pos.x, dims.x),
+                reflect(pos.y, dims.y),
+                reflect(pos.z, dims.z),
+            )),
+            Boundary::Klein => {
+                // Every full trip across y mirrors x once
+                let x = pos.x.rem_euclid(dims.x);
+                let x = if pos.y.div_euclid(dims.y) % 2 != 0 {
+                    dims.x - 1 - x
+                } else {
+                    x
+                };
+                Resolved::Cell(IVec3::new(
+                    x,
+                    pos.y.rem_euclid(dims.y),
+                    pos.z.rem_euclid(dims.z),
+                ))
+            }
+        }
+    }
+}
+
+/// Reflects `i` back into `0..n`, repeating the edge cell: `-1 -> 0`, `n -> n-1`
+fn reflect(i: Index, n: Index) -> Index {
+    let period = 2 * n;
+    let i = i.rem_euclid(period);
+    if i < n {
+        i
+    } else {
+        period - 1 - i
+    }
+}
+
+pub trait GridNew<C: Cell = bool>: Grid<C> {
     fn new(dims: IVec3) -> Self;
 }
 
@@ -64,6 +590,8 @@ mod tests {
     use super::*;
     use crate::BitFlipper;
 
+    use rstest::*;
+
     #[derive(Debug, PartialEq, Eq)]
     struct TestGridWithMut<'a, G: Grid> {
         grid: G,
@@ -94,11 +622,37 @@ mod tests {
             self.grid.flip(x, y, z)
         }
 
-        fn resize(&mut self, new_dims: IVec3) {
-            unreachable!("Not expected to be called by bitflipper: resize(new_dims: {new_dims:?})");
+        fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+            unreachable!(
+                "Not expected to be called by bitflipper: resize_with_anchor(new_dims: {new_dims:?}, anchor: {anchor:?})"
+            );
         }
     }
 
+    #[rstest]
+    #[case::wrap_left(Boundary::Wrap, (-1, 1), Resolved::Cell(IVec3::new(3, 1, 0)))]
+    #[case::wrap_far(Boundary::Wrap, (9, 6), Resolved::Cell(IVec3::new(1, 0, 0)))]
+    #[case::dead(Boundary::Dead, (4, 1), Resolved::Constant(false))]
+    #[case::dead_in_bounds(Boundary::Dead, (3, 2), Resolved::Cell(IVec3::new(3, 2, 0)))]
+    #[case::alive(Boundary::Alive, (0, -1), Resolved::Constant(true))]
+    #[case::reflect_left(Boundary::Reflect, (-1, 1), Resolved::Cell(IVec3::new(0, 1, 0)))]
+    #[case::reflect_left_2(Boundary::Reflect, (-2, 1), Resolved::Cell(IVec3::new(1, 1, 0)))]
+    #[case::reflect_right(Boundary::Reflect, (4, 3), Resolved::Cell(IVec3::new(3, 2, 0)))]
+    #[case::reflect_far(Boundary::Reflect, (9, 1), Resolved::Cell(IVec3::new(1, 1, 0)))]
+    #[case::klein_left(Boundary::Klein, (-1, 1), Resolved::Cell(IVec3::new(3, 1, 0)))]
+    #[case::klein_top(Boundary::Klein, (0, -1), Resolved::Cell(IVec3::new(3, 2, 0)))]
+    #[case::klein_bottom(Boundary::Klein, (1, 3), Resolved::Cell(IVec3::new(2, 0, 0)))]
+    #[case::klein_corner(Boundary::Klein, (-1, -1), Resolved::Cell(IVec3::new(0, 2, 0)))]
+    #[case::klein_twice(Boundary::Klein, (1, 6), Resolved::Cell(IVec3::new(1, 0, 0)))]
+    fn check_boundary_resolve(
+        #[case] boundary: Boundary,
+        #[case] (x, y): (Index, Index),
+        #[case] expected: Resolved,
+    ) {
+        let dims = IVec3::new(4, 3, 1);
+        assert_eq!(boundary.resolve(dims, IVec3::new(x, y, 0)), expected);
+    }
+
     // Make sure certain types of Grid impls are possible
     #[test]
     fn check_flip_tracking_with_mut() {
@@ -142,4 +696,102 @@ mod tests {
         assert_eq!(bitflipper.grid().flipped, &[(3, 3, 3)]);
         bitflipper.grid_mut().flipped.clear();
     }
+
+    /// Renders the first layer of `grid` with `O` for set cells, one string per row
+    fn rows(grid: &crate::BitGrid) -> Vec<alloc::string::String> {
+        (0..grid.height())
+            .map(|y| {
+                (0..grid.width())
+                    .map(|x| if grid.get(x, y, 0) { 'O' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn pattern(lines: &[&str]) -> crate::BitGrid {
+        crate::BitGrid::new_with_fn(lines[0].len(), lines.len(), 1, |x, y, _| {
+            lines[y as usize].as_bytes()[x as usize] == b'O'
+        })
+    }
+
+    #[rstest]
+    #[case::identity(Transform::Identity, &["OO.", "..O"])]
+    #[case::rotate_90(Transform::Rotate90, &[".O", ".O", "O."])]
+    #[case::rotate_180(Transform::Rotate180, &["O..", ".OO"])]
+    #[case::rotate_270(Transform::Rotate270, &[".O", "O.", "O."])]
+    #[case::flip_x(Transform::FlipX, &[".OO", "O.."])]
+    #[case::flip_y(Transform::FlipY, &["..O", "OO."])]
+    #[case::transpose(Transform::Transpose, &["O.", "O.", ".O"])]
+    #[case::anti_transpose(Transform::AntiTranspose, &["O.", ".O", ".O"])]
+    fn check_transformed(#[case] transform: Transform, #[case] expected: &[&str]) {
+        let grid = pattern(&["OO.", "..O"]);
+        let turned = grid.transformed(transform);
+
+        assert_eq!(rows(&turned), expected);
+        assert_eq!(turned.transformed(transform.inverse()), grid);
+    }
+
+    #[test]
+    fn check_transforms_are_distinct() {
+        // The R-pentomino has no symmetry, so every transform gives a different result
+        let r = pattern(&[".OO", "OO.", ".O."]);
+        let mut seen: Vec<_> = Transform::ALL
+            .iter()
+            .map(|&t| rows(&r.transformed(t)))
+            .collect();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 8);
+    }
+
+    #[rstest]
+    #[case::or(Blend::Or, &["OOO.", ".O..", "...."])]
+    #[case::xor(Blend::Xor, &["O.O.", ".O..", "...."])]
+    #[case::overwrite(Blend::Overwrite, &["OOO.", "....", "...."])]
+    fn check_stamp_blend(#[case] blend: Blend, #[case] expected: &[&str]) {
+        let mut grid = pattern(&["OO..", ".O..", "...."]);
+        let stamp = pattern(&["OO", ".."]);
+        grid.stamp_with(
+            &stamp,
+            IVec3::new(1, 0, 0),
+            Transform::Identity,
+            blend,
+            Boundary::Dead,
+        );
+        assert_eq!(rows(&grid), expected);
+    }
+
+    #[rstest]
+    #[case::clip(Boundary::Dead, &["O...", "....", "...."])]
+    #[case::wrap(Boundary::Wrap, &["O..O", "....", "O..O"])]
+    fn check_stamp_edges(#[case] edges: Boundary, #[case] expected: &[&str]) {
+        let mut grid = crate::BitGrid::new(4, 3, 1);
+        let block = pattern(&["OO", "OO"]);
+        grid.stamp_with(
+            &block,
+            IVec3::new(-1, -1, 0),
+            Transform::Identity,
+            Blend::Or,
+            edges,
+        );
+        assert_eq!(rows(&grid), expected);
+    }
+
+    #[test]
+    fn check_stamp_many_states() {
+        let mut grid: crate::PackedGrid<u8> = crate::PackedGrid::new(3, 2, 1);
+        grid.fill(2);
+
+        let stamp = pattern(&["O.", ".O"]);
+        grid.stamp_with(
+            &stamp,
+            IVec3::new(1, 0, 0),
+            Transform::FlipX,
+            Blend::Overwrite,
+            Boundary::Dead,
+        );
+
+        let cells: Vec<u8> = grid.iter().map(|(_, state)| state).collect();
+        assert_eq!(cells, [2, 0, 1, 2, 1, 0]);
+    }
 }
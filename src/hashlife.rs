@@ -0,0 +1,834 @@
+use crate::prelude::*;
+
+use std::collections::HashMap;
+
+type NodeId = u32;
+
+/// Id of the level 0 node holding a **dead** cell
+const DEAD: NodeId = 0;
+
+/// Id of the level 0 node holding an **alive** cell
+const ALIVE: NodeId = 1;
+
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    /// A node at level `k` covers a `2^k` by `2^k` square. Level 0 nodes are single cells.
+    level: u8,
+
+    /// The `[nw, ne, sw, se]` quadrants of this node, each one level lower. Unused for level 0.
+    children: [NodeId; 4],
+
+    /// Number of alive cells in this node
+    population: u64,
+}
+
+/// A [Hashlife](https://en.wikipedia.org/wiki/Hashlife) engine for Life-like rules on an unbounded plane.
+///
+/// The plane is stored as a quadtree where identical subtrees are shared ("hash-consed"),
+/// and the future of each subtree is memoised. Repetitive patterns like glider guns and
+/// spaceships can then be advanced billions of generations in a few milliseconds.
+///
+/// Unlike [`Life`](crate::Life), coordinates are `i64`, can be negative, and never wrap.
+///
+/// # Stepping
+/// - [`step`](Self::step) advances by `2^step_log2` generations at once ("hyperspeed"). See [`set_step_log2`](Self::set_step_log2).
+/// - [`advance`](Self::advance) advances by an exact number of generations.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{BitGrid, Hashlife, LifeRule};
+/// // A right-facing glider
+/// let glider = BitGrid::new_with_fn(3, 3, 1, |x, y, _z| {
+///     matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2))
+/// });
+/// let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &glider);
+///
+/// // Gliders move 1 cell diagonally every 4 generations
+/// life.advance(1_000_000_000);
+/// assert_eq!(life.generation(), 1_000_000_000);
+/// assert_eq!(life.population(), 5);
+///
+/// let moved = life.to_bitgrid(250_000_000, 250_000_000, 3, 3);
+/// assert_eq!(moved, glider);
+/// ```
+#[derive(Clone)]
+pub struct Hashlife {
+    rule: LifeRule,
+
+    /// Every node ever created, indexed by `NodeId`
+    nodes: Vec<Node>,
+
+    /// Looks up the node with the given children, so that identical nodes are only stored once
+    index: HashMap<[NodeId; 4], NodeId>,
+
+    /// Memoised results, keyed by node and log2 of the number of generations advanced
+    results: HashMap<(NodeId, u8), NodeId>,
+
+    /// The empty node at each level
+    empty: Vec<NodeId>,
+
+    /// The whole plane. Its center is at `(0, 0)`.
+    root: NodeId,
+
+    generation: u64,
+
+    step_log2: u8,
+}
+
+/// Basic Usage
+impl Hashlife {
+    /// Smallest level the root is kept at, so that it always has grandchildren
+    const MIN_LEVEL: u8 = 3;
+
+    /// Creates a new `Hashlife` engine with every cell initially **dead**.
+    ///
+    /// # Panics
+    /// Rules with `B0` can't be simulated on an unbounded plane.
+    #[track_caller]
+    pub fn new(rule: LifeRule) -> Self {
+        assert!(
+            !rule.has_b0(),
+            "Hashlife can't simulate B0 rules on an unbounded plane, but was given {rule}"
+        );
+
+        let mut life = Self {
+            rule,
+            nodes: vec![
+                Node {
+                    level: 0,
+                    children: [DEAD; 4],
+                    population: 0,
+                },
+                Node {
+                    level: 0,
+                    children: [DEAD; 4],
+                    population: 1,
+                },
+            ],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            generation: 0,
+            step_log2: 0,
+        };
+        life.root = life.empty(Self::MIN_LEVEL);
+
+        life
+    }
+
+    /// Creates a new `Hashlife` engine holding the cells of `grid`, with its `(0, 0)` cell at `(0, 0)`.
+    ///
+    /// Only the first layer (`z == 0`) of `grid` is used.
+    ///
+    /// # Panics
+    /// Rules with `B0` can't be simulated on an unbounded plane.
+    #[track_caller]
+    pub fn from_bitgrid(rule: LifeRule, grid: &BitGrid) -> Self {
+        let mut life = Self::new(rule);
+
+        // Build the grid as its own quadtree, then hang it off the bottom right of the origin
+        let size = grid.width().max(grid.height()).max(1) as u64;
+        let level = (size.next_power_of_two().trailing_zeros() as u8).max(Self::MIN_LEVEL - 1);
+        let patch = life.build(grid, level, 0, 0);
+        let e = life.empty(level);
+        life.root = life.join([e, e, e, patch]);
+
+        life
+    }
+
+    /// The rule this engine steps under
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// Number of generations advanced so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Number of **alive** cells on the whole plane
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    /// Number of nodes stored, including ones that are no longer reachable until [`clear_cache`](Self::clear_cache) is called
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks whether the cell at `(x, y)` is **alive** or **dead**.
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        let level = self.level(self.root);
+        let half = 1_i64 << (level - 1);
+        if x < -half || y < -half || x >= half || y >= half {
+            return false;
+        }
+
+        // Walk down using coordinates relative to the top left of the current node
+        let (mut x, mut y) = ((x + half) as u64, (y + half) as u64);
+        let mut id = self.root;
+        while self.level(id) > 0 {
+            let half = 1_u64 << (self.level(id) - 1);
+            let quadrant = ((y >= half) as usize) * 2 + (x >= half) as usize;
+            id = self.nodes[id as usize].children[quadrant];
+            x %= half;
+            y %= half;
+        }
+
+        id == ALIVE
+    }
+
+    /// Sets the cell at `(x, y)` to either **alive** or **dead**, returning its previous state.
+    pub fn set(&mut self, x: i64, y: i64, is_alive: bool) -> bool {
+        while !self.contains(x, y) {
+            self.root = self.expand(self.root);
+        }
+
+        let old = self.get(x, y);
+        if old != is_alive {
+            let half = 1_i64 << (self.level(self.root) - 1);
+            let (x, y) = ((x + half) as u64, (y + half) as u64);
+            self.root = self.set_in(self.root, x, y, is_alive);
+        }
+
+        old
+    }
+
+    /// Marks all cells as **dead**. The generation counter is left as-is.
+    pub fn clear(&mut self) {
+        self.root = self.empty(Self::MIN_LEVEL);
+    }
+
+    /// Copies every cell of `grid` onto the plane with its `(0, 0)` cell at `(x, y)`.
+    ///
+    /// Dead cells in `grid` overwrite alive cells on the plane. Only the first layer (`z == 0`) is used.
+    pub fn write_bitgrid(&mut self, grid: &BitGrid, x: i64, y: i64) {
+        for gy in 0..grid.height() {
+            for gx in 0..grid.width() {
+                let (px, py) = (x + gx as i64, y + gy as i64);
+                let is_alive = grid.get(gx, gy, 0);
+                if is_alive || self.get(px, py) {
+                    self.set(px, py, is_alive);
+                }
+            }
+        }
+    }
+
+    /// Copies the `width` by `height` region with its top left at `(x, y)` into a new [`BitGrid`].
+    pub fn to_bitgrid(&self, x: i64, y: i64, width: usize, height: usize) -> BitGrid {
+        let mut grid = BitGrid::new(width, height, 1);
+        for gy in 0..height {
+            for gx in 0..width {
+                if self.get(x + gx as i64, y + gy as i64) {
+                    grid.set(gx as Index, gy as Index, 0, true);
+                }
+            }
+        }
+        grid
+    }
+
+    /// The smallest rectangle containing every **alive** cell, as `(min, max)` corners, both inclusive.
+    ///
+    /// Returns `None` if every cell is **dead**.
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        if self.population() == 0 {
+            return None;
+        }
+
+        let half = 1_i64 << (self.level(self.root) - 1);
+        let mut min = (i64::MAX, i64::MAX);
+        let mut max = (i64::MIN, i64::MIN);
+        self.visit_bounds(self.root, -half, -half, &mut min, &mut max);
+
+        Some((min, max))
+    }
+}
+
+/// Stepping
+impl Hashlife {
+    /// Log2 of the number of generations [`step`](Self::step) advances by
+    pub fn step_log2(&self) -> u8 {
+        self.step_log2
+    }
+
+    /// Sets the number of generations [`step`](Self::step) advances by to `2^step_log2`.
+    ///
+    /// Memoised results are kept for every step size, so switching back and forth is cheap.
+    ///
+    /// # Panics
+    /// If `step_log2` is above `60`.
+    #[track_caller]
+    pub fn set_step_log2(&mut self, step_log2: u8) {
+        assert!(
+            step_log2 <= 60,
+            "Steps of 2^{step_log2} generations would overflow the plane"
+        );
+        self.step_log2 = step_log2;
+    }
+
+    /// Advances the simulation by `2^step_log2` generations. See [`set_step_log2`](Self::set_step_log2).
+    pub fn step(&mut self) {
+        self.advance_log2(self.step_log2);
+    }
+
+    /// Advances the simulation by exactly `generations` generations.
+    ///
+    /// This takes one hyperspeed step for each set bit of `generations`.
+    ///
+    /// # Panics
+    /// If `generations` is `2^61` or more, as single steps are limited to `2^60` generations like [`set_step_log2`](Self::set_step_log2).
+    #[track_caller]
+    pub fn advance(&mut self, generations: u64) {
+        assert!(
+            generations >> 61 == 0,
+            "Can't advance by {generations} generations at once, the most is 2^61 - 1"
+        );
+
+        for log2 in (0..64).rev() {
+            if (generations >> log2) & 1 != 0 {
+                self.advance_log2(log2);
+            }
+        }
+    }
+
+    /// Drops all memoised results, along with every node the plane no longer uses.
+    ///
+    /// Nodes and results are never freed otherwise, so long runs of a chaotic pattern grow without bound.
+    /// Calling this every so often keeps memory in check, at the cost of recomputing results that come up again.
+    pub fn clear_cache(&mut self) {
+        self.results.clear();
+
+        // Mark every node reachable from the root, plus the empty nodes which are kept for reuse
+        let mut keep = vec![false; self.nodes.len()];
+        keep[DEAD as usize] = true;
+        keep[ALIVE as usize] = true;
+        let mut stack: Vec<NodeId> = self.empty.clone();
+        stack.push(self.root);
+        while let Some(id) = stack.pop() {
+            if !keep[id as usize] {
+                keep[id as usize] = true;
+                stack.extend(self.children(id));
+            }
+        }
+
+        // Children are always created before their parents, so one pass renumbers every node
+        let mut ids = vec![DEAD; self.nodes.len()];
+        let mut nodes = Vec::new();
+        for (id, node) in self.nodes.iter().enumerate() {
+            if keep[id] {
+                let mut node = *node;
+                if node.level > 0 {
+                    node.children = node.children.map(|c| ids[c as usize]);
+                }
+                ids[id] = nodes.len() as NodeId;
+                nodes.push(node);
+            }
+        }
+
+        self.index = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.level > 0)
+            .map(|(id, node)| (node.children, id as NodeId))
+            .collect();
+        for e in &mut self.empty {
+            *e = ids[*e as usize];
+        }
+        self.root = ids[self.root as usize];
+        self.nodes = nodes;
+    }
+
+    fn advance_log2(&mut self, log2: u8) {
+        assert!(
+            log2 <= 60,
+            "Steps of 2^{log2} generations would overflow the plane"
+        );
+
+        // The pattern must sit in the center quarter of the root with a margin of at least 2^log2 cells,
+        // so that nothing can escape the center half that `result` returns.
+        while self.level(self.root) < log2 + 3 || !self.is_padded(self.root) {
+            self.root = self.expand(self.root);
+        }
+
+        self.root = self.result(self.root, log2);
+        self.generation += 1 << log2;
+    }
+
+    /// The center half of `id` (one level lower) advanced by `2^min(level - 2, log2)` generations.
+    fn result(&mut self, id: NodeId, log2: u8) -> NodeId {
+        let level = self.level(id);
+        debug_assert!(level >= 2);
+
+        let log2 = log2.min(level - 2);
+        if let Some(&result) = self.results.get(&(id, log2)) {
+            return result;
+        }
+
+        let result = if self.nodes[id as usize].population == 0 {
+            self.empty(level - 1)
+        } else if level == 2 {
+            self.step_level_2(id)
+        } else {
+            // Nine overlapping subnodes, each a quarter of the size of `id`
+            let [nw, ne, sw, se] = self.children(id);
+            let n00 = nw;
+            let n01 = self.centered_horizontal(nw, ne);
+            let n02 = ne;
+            let n10 = self.centered_vertical(nw, sw);
+            let n11 = self.center(id);
+            let n12 = self.centered_vertical(ne, se);
+            let n20 = sw;
+            let n21 = self.centered_horizontal(sw, se);
+            let n22 = se;
+
+            if log2 == level - 2 {
+                // Full speed: advance twice by 2^(level - 3)
+                let r = [n00, n01, n02, n10, n11, n12, n20, n21, n22].map(|n| self.result(n, log2));
+                let quads = [
+                    self.join([r[0], r[1], r[3], r[4]]),
+                    self.join([r[1], r[2], r[4], r[5]]),
+                    self.join([r[3], r[4], r[6], r[7]]),
+                    self.join([r[4], r[5], r[7], r[8]]),
+                ];
+                let q = quads.map(|n| self.result(n, log2));
+                self.join(q)
+            } else {
+                // Slow: take the center of each subnode without advancing, and only advance once
+                let r = [n00, n01, n02, n10, n11, n12, n20, n21, n22].map(|n| self.center(n));
+                let quads = [
+                    self.join([r[0], r[1], r[3], r[4]]),
+                    self.join([r[1], r[2], r[4], r[5]]),
+                    self.join([r[3], r[4], r[6], r[7]]),
+                    self.join([r[4], r[5], r[7], r[8]]),
+                ];
+                let q = quads.map(|n| self.result(n, log2));
+                self.join(q)
+            }
+        };
+
+        self.results.insert((id, log2), result);
+        result
+    }
+
+    /// Steps the center 2x2 of a 4x4 node once by brute force
+    fn step_level_2(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_in(id, x as u64, y as u64);
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, (cx, cy)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let mut live_count = 0;
+            for dy in [-1_i32, 0, 1] {
+                for dx in [-1_i32, 0, 1] {
+                    if (dx, dy) != (0, 0) {
+                        live_count += cells[(cy + dy) as usize][(cx + dx) as usize] as u8;
+                    }
+                }
+            }
+
+            if self
+                .rule
+                .next_state(cells[cy as usize][cx as usize], live_count)
+            {
+                next[i] = ALIVE;
+            }
+        }
+
+        self.join(next)
+    }
+}
+
+/// Quadtree helpers
+impl Hashlife {
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn children(&self, id: NodeId) -> [NodeId; 4] {
+        self.nodes[id as usize].children
+    }
+
+    /// The canonical node with these `[nw, ne, sw, se]` children
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+
+        let level = self.level(children[0]) + 1;
+        let population = children
+            .iter()
+            .map(|&c| self.nodes[c as usize].population)
+            .sum();
+
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level,
+            children,
+            population,
+        });
+        self.index.insert(children, id);
+
+        id
+    }
+
+    /// The empty node at `level`
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.join([e; 4]);
+            self.empty.push(next);
+        }
+
+        self.empty[level as usize]
+    }
+
+    /// Wraps `id` in a node one level higher, keeping it centered on the same point
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let level = self.level(id);
+        let e = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.children(id);
+
+        let nw = self.join([e, e, e, nw]);
+        let ne = self.join([e, e, ne, e]);
+        let sw = self.join([e, sw, e, e]);
+        let se = self.join([se, e, e, e]);
+        self.join([nw, ne, sw, se])
+    }
+
+    /// The center half of `id`, one level lower
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(id);
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    /// The node between `w` and `e`, which are side by side
+    fn centered_horizontal(&mut self, w: NodeId, e: NodeId) -> NodeId {
+        let [_, w_ne, _, w_se] = self.children(w);
+        let [e_nw, _, e_sw, _] = self.children(e);
+        self.join([w_ne, e_nw, w_se, e_sw])
+    }
+
+    /// The node between `n` and `s`, which are stacked on top of each other
+    fn centered_vertical(&mut self, n: NodeId, s: NodeId) -> NodeId {
+        let [_, _, n_sw, n_se] = self.children(n);
+        let [s_nw, s_ne, _, _] = self.children(s);
+        self.join([n_sw, n_se, s_nw, s_ne])
+    }
+
+    /// Whether every alive cell of `id` is in its center quarter
+    fn is_padded(&self, id: NodeId) -> bool {
+        let [nw, ne, sw, se] = self.children(id);
+        let inner = [
+            self.children(self.children(nw)[3])[3],
+            self.children(self.children(ne)[2])[2],
+            self.children(self.children(sw)[1])[1],
+            self.children(self.children(se)[0])[0],
+        ];
+        let inner_population: u64 = inner
+            .iter()
+            .map(|&c| self.nodes[c as usize].population)
+            .sum();
+
+        inner_population == self.nodes[id as usize].population
+    }
+
+    /// Whether `(x, y)` is covered by the root
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let half = 1_i64 << (self.level(self.root) - 1);
+        (-half..half).contains(&x) && (-half..half).contains(&y)
+    }
+
+    /// Checks the cell at `(x, y)` relative to the top left of `id`
+    fn get_in(&self, id: NodeId, x: u64, y: u64) -> bool {
+        let level = self.level(id);
+        if level == 0 {
+            return id == ALIVE;
+        }
+
+        let half = 1_u64 << (level - 1);
+        let quadrant = ((y >= half) as usize) * 2 + (x >= half) as usize;
+        self.get_in(self.children(id)[quadrant], x % half, y % half)
+    }
+
+    /// A copy of `id` with the cell at `(x, y)`, relative to its top left, set to `is_alive`
+    fn set_in(&mut self, id: NodeId, x: u64, y: u64, is_alive: bool) -> NodeId {
+        let level = self.level(id);
+        if level == 0 {
+            return if is_alive { ALIVE } else { DEAD };
+        }
+
+        let half = 1_u64 << (level - 1);
+        let quadrant = ((y >= half) as usize) * 2 + (x >= half) as usize;
+        let mut children = self.children(id);
+        children[quadrant] = self.set_in(children[quadrant], x % half, y % half, is_alive);
+
+        self.join(children)
+    }
+
+    /// Builds the `2^level` square of `grid` with its top left at `(x, y)`. Cells outside of `grid` are dead.
+    fn build(&mut self, grid: &BitGrid, level: u8, x: Index, y: Index) -> NodeId {
+        if x >= grid.width() || y >= grid.height() {
+            return self.empty(level);
+        }
+
+        if level == 0 {
+            return if grid.get(x, y, 0) { ALIVE } else { DEAD };
+        }
+
+        let half = 1 << (level - 1);
+        let children = [
+            self.build(grid, level - 1, x, y),
+            self.build(grid, level - 1, x + half, y),
+            self.build(grid, level - 1, x, y + half),
+            self.build(grid, level - 1, x + half, y + half),
+        ];
+
+        self.join(children)
+    }
+
+    fn visit_bounds(&self, id: NodeId, x: i64, y: i64, min: &mut (i64, i64), max: &mut (i64, i64)) {
+        let node = &self.nodes[id as usize];
+        if node.population == 0 {
+            return;
+        }
+
+        if node.level == 0 {
+            *min = (min.0.min(x), min.1.min(y));
+            *max = (max.0.max(x), max.1.max(y));
+            return;
+        }
+
+        // Skip quadrants that can't grow the box we already have
+        let size = 1_i64 << node.level;
+        if x >= min.0 && y >= min.1 && x + size - 1 <= max.0 && y + size - 1 <= max.1 {
+            return;
+        }
+
+        let half = size / 2;
+        let [nw, ne, sw, se] = node.children;
+        self.visit_bounds(nw, x, y, min, max);
+        self.visit_bounds(ne, x + half, y, min, max);
+        self.visit_bounds(sw, x, y + half, min, max);
+        self.visit_bounds(se, x + half, y + half, min, max);
+    }
+}
+
+impl core::fmt::Debug for Hashlife {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Hashlife")
+            .field("rule", &self.rule)
+            .field("generation", &self.generation)
+            .field("population", &self.population())
+            .field("nodes", &self.nodes.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Life;
+
+    use pretty_assertions::assert_eq;
+
+    fn glider() -> BitGrid {
+        BitGrid::new_with_fn(3, 3, 1, |x, y, _z| {
+            matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2))
+        })
+    }
+
+    fn r_pentomino() -> BitGrid {
+        BitGrid::new_with_fn(3, 3, 1, |x, y, _z| {
+            matches!((x, y), (1, 0) | (2, 0) | (0, 1) | (1, 1) | (1, 2))
+        })
+    }
+
+    #[test]
+    fn check_get_set() {
+        let mut life = Hashlife::new(LifeRule::CONWAY);
+        assert_eq!(life.population(), 0);
+        assert_eq!(life.bounding_box(), None);
+
+        for (x, y) in [(0, 0), (-5, 3), (1000, -2000), (-1, -1)] {
+            assert!(!life.set(x, y, true));
+            assert!(life.get(x, y));
+        }
+        assert_eq!(life.population(), 4);
+        assert_eq!(life.bounding_box(), Some(((-5, -2000), (1000, 3))));
+
+        assert!(life.set(1000, -2000, false));
+        assert!(!life.get(1000, -2000));
+        assert_eq!(life.population(), 3);
+        assert_eq!(life.bounding_box(), Some(((-5, -1), (0, 3))));
+    }
+
+    #[test]
+    fn check_bitgrid_round_trip() {
+        let grid = BitGrid::new_with_fn(37, 21, 1, |x, y, _z| (x * 3 + y * 7 + x * y) % 5 == 0);
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &grid);
+        assert_eq!(life.population(), grid.count_set() as u64);
+        assert_eq!(life.to_bitgrid(0, 0, 37, 21), grid);
+
+        // Writing somewhere else leaves the original alone
+        life.write_bitgrid(&grid, -100, 50);
+        assert_eq!(life.to_bitgrid(-100, 50, 37, 21), grid);
+        assert_eq!(life.to_bitgrid(0, 0, 37, 21), grid);
+        assert_eq!(life.population(), 2 * grid.count_set() as u64);
+
+        // Dead cells overwrite
+        life.write_bitgrid(&BitGrid::new(37, 21, 1), 0, 0);
+        assert_eq!(life.population(), grid.count_set() as u64);
+    }
+
+    #[test]
+    fn check_matches_life() {
+        // Big enough that the R-pentomino doesn't wrap around within 300 generations
+        let size = 256;
+        let offset = 128;
+
+        let mut life: Life = Life::new(size, size);
+        let mut hashlife = Hashlife::new(LifeRule::CONWAY);
+        let pattern = r_pentomino();
+        for y in 0..pattern.height() {
+            for x in 0..pattern.width() {
+                if pattern.get(x, y, 0) {
+                    life.set((x + offset) as i16, (y + offset) as i16, true);
+                    hashlife.set((x + offset) as i64, (y + offset) as i64, true);
+                }
+            }
+        }
+
+        for generation in 1..=300 {
+            life.step();
+            hashlife.advance(1);
+            assert_eq!(hashlife.generation(), generation);
+
+            if generation % 50 == 0 {
+                let expected = life.cells().clone();
+                let actual = hashlife.to_bitgrid(0, 0, size, size);
+                assert_eq!(actual, expected, "Mismatch in generation {generation}");
+            }
+        }
+    }
+
+    #[test]
+    fn check_hyperspeed_matches_exact() {
+        let mut exact = Hashlife::from_bitgrid(LifeRule::CONWAY, &r_pentomino());
+        let mut fast = exact.clone();
+
+        exact.advance(1024);
+
+        fast.set_step_log2(8);
+        for _ in 0..4 {
+            fast.step();
+        }
+
+        assert_eq!(fast.generation(), 1024);
+        assert_eq!(fast.population(), exact.population());
+        assert_eq!(fast.bounding_box(), exact.bounding_box());
+
+        let ((x0, y0), (x1, y1)) = exact.bounding_box().unwrap();
+        let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+        assert_eq!(
+            fast.to_bitgrid(x0, y0, w, h),
+            exact.to_bitgrid(x0, y0, w, h)
+        );
+    }
+
+    #[test]
+    fn check_r_pentomino_stabilizes() {
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &r_pentomino());
+
+        // The R-pentomino famously settles down after 1103 generations with 116 cells, 6 of them in escaping gliders
+        life.advance(1103);
+        assert_eq!(life.population(), 116);
+
+        life.advance(10_000);
+        assert_eq!(life.population(), 116);
+    }
+
+    #[test]
+    fn check_glider_hyperspeed() {
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &glider());
+        life.set_step_log2(20);
+        life.step();
+        assert_eq!(life.generation(), 1 << 20);
+
+        // One cell diagonally per 4 generations
+        let moved = 1 << 18;
+        assert_eq!(life.to_bitgrid(moved, moved, 3, 3), glider());
+        assert_eq!(life.population(), 5);
+    }
+
+    #[test]
+    fn check_other_rules() {
+        // HighLife's replicator makes copies of itself, which Conway's rules don't
+        let replicator = BitGrid::new_with_fn(5, 5, 1, |x, y, _z| {
+            matches!(
+                (x, y),
+                (2, 0)
+                    | (3, 0)
+                    | (4, 0)
+                    | (1, 1)
+                    | (4, 1)
+                    | (0, 2)
+                    | (4, 2)
+                    | (0, 3)
+                    | (3, 3)
+                    | (0, 4)
+                    | (1, 4)
+                    | (2, 4)
+            )
+        });
+        let mut life = Hashlife::from_bitgrid(LifeRule::HIGHLIFE, &replicator);
+        life.advance(12);
+        assert_eq!(life.population(), 24);
+    }
+
+    #[test]
+    fn check_clear_cache_drops_unused_nodes() {
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &r_pentomino());
+        life.advance(500);
+        let mut cleared = life.clone();
+
+        let before = cleared.node_count();
+        cleared.clear_cache();
+        assert!(cleared.node_count() < before);
+        assert_eq!(cleared.population(), life.population());
+        assert_eq!(cleared.bounding_box(), life.bounding_box());
+
+        // Shared nodes are still found after renumbering, and stepping gives the same plane
+        life.advance(1000);
+        cleared.advance(1000);
+        let ((x0, y0), (x1, y1)) = life.bounding_box().unwrap();
+        let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+        assert_eq!(
+            cleared.to_bitgrid(x0, y0, w, h),
+            life.to_bitgrid(x0, y0, w, h)
+        );
+        assert_eq!(cleared.population(), 116);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_advance_too_far_panics() {
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &glider());
+        life.advance(1 << 61);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_b0_panics() {
+        let _ = Hashlife::new(LifeRule::parse("B0/S8").unwrap());
+    }
+}
@@ -8,24 +8,96 @@
 extern crate alloc;
 
 pub mod grid;
+pub use grid::Anchor;
+pub use grid::Blend;
+pub use grid::Boundary;
+pub use grid::Cell;
 pub use grid::Grid;
 pub use grid::GridNew;
+pub use grid::Transform;
 
 mod life;
 pub use life::Life;
 
+mod rule;
+pub use rule::AutomatonRule;
+pub use rule::CodeKind;
+pub use rule::ElementryRule;
+pub use rule::GenerationsRule;
+pub use rule::LifeRule;
+
+mod generations;
+pub use generations::Generations;
+
+#[cfg(feature = "std")]
+mod hashlife;
+#[cfg(feature = "std")]
+pub use hashlife::Hashlife;
+
+#[cfg(feature = "std")]
+mod sparse;
+#[cfg(feature = "std")]
+pub use sparse::SparseGrid;
+
+mod wireworld;
+pub use wireworld::{Wireworld, WireworldCell};
+
+mod rle;
+pub use rle::Rle;
+
+mod automaton;
+pub use automaton::Automaton;
+
+mod basin;
+pub use basin::{Attractor, Basins, StateGraph};
+
+mod preimage;
+pub use preimage::Preimages;
+
+mod spacetime;
+pub use spacetime::SpaceTime;
+
 mod elementry;
 pub use elementry::Elementry;
 
+mod rng;
+pub use rng::Rule30Rng;
+
 mod bitgrid;
 pub use bitgrid::BitGrid;
 
+mod packed;
+pub use packed::PackedGrid;
+
+mod pattern;
+pub use pattern::{Pattern, PatternKind, PatternRule};
+
+mod apgcode;
+pub use apgcode::Apgcode;
+
+mod census;
+pub use census::{Census, CensusObject};
+
+mod cycle;
+pub use cycle::{Cycle, CycleDetector};
+
+mod stats;
+pub use stats::{StepStats, TimeSeries};
+
+mod simulation;
+pub use simulation::{Simulation, StepReport};
+
 mod bitflipper;
 pub use bitflipper::BitFlipper;
 
 pub mod prelude {
     pub use crate::bitflipper::BitFlipper;
     pub use crate::bitgrid::BitGrid;
-    pub use crate::grid::{Grid, GridNew, Index};
+    pub use crate::grid::{Anchor, Blend, Boundary, Cell, Grid, GridNew, Index, Transform};
+    pub use crate::packed::PackedGrid;
+    pub use crate::rule::{AutomatonRule, ElementryRule, GenerationsRule, LifeRule};
+    pub use crate::simulation::{Simulation, StepReport};
+    #[cfg(feature = "std")]
+    pub use crate::sparse::SparseGrid;
     pub use ultraviolet::{IVec2, IVec3};
 }
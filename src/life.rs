@@ -1,4 +1,9 @@
 use crate::prelude::*;
+use crate::stats::StatsRecorder;
+use crate::{StepStats, TimeSeries};
+
+use alloc::string::{String, ToString};
+use alloc::vec;
 
 #[derive(Clone)]
 pub struct Life<G: Grid = crate::BitGrid> {
@@ -7,21 +12,62 @@ pub struct Life<G: Grid = crate::BitGrid> {
 
     /// Scratch copy of cells used when stepping the simulation
     scratch: G,
+
+    /// How cells beyond the edges of `cells` are treated
+    boundary: Boundary,
+
+    /// Which cells are born and survive each step
+    rule: LifeRule,
+
+    /// Number of steps taken since creation or the last reset
+    generation: u64,
+
+    /// Stats of every step, while they're being recorded
+    stats: StatsRecorder,
 }
 
 /// Basic Usage
 impl<G: GridNew + Clone> Life<G> {
     /// Creates a new `Life` simulation with the given dimensions where all cells are initially **dead**.
+    ///
+    /// The simulation runs Conway's Game of Life. See [`new_with_rule`](Self::new_with_rule) for other rules.
     pub fn new(width: usize, height: usize) -> Self {
-        Self::new_with_cells(G::new(IVec3::new(width as Index, height as Index, 1)))
+        Self::new_with_rule(LifeRule::CONWAY, width, height)
+    }
+
+    /// Creates a new `Life` simulation with the given rule and dimensions where all cells are initially **dead**.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{BitGrid, Life, LifeRule};
+    /// let highlife: Life<BitGrid> = Life::new_with_rule(LifeRule::parse("B36/S23").unwrap(), 16, 16);
+    /// assert_eq!(highlife.rule(), LifeRule::HIGHLIFE);
+    /// ```
+    pub fn new_with_rule(rule: LifeRule, width: usize, height: usize) -> Self {
+        Self::new_with_cells_and_rule(rule, G::new(IVec3::new(width as Index, height as Index, 1)))
     }
 }
 
 impl<G: Grid + Clone> Life<G> {
     /// Creates a new `Life` simulation with the given cells
+    ///
+    /// The simulation uses the [`Boundary`] of `cells`. See [`set_boundary`](Self::set_boundary) to override it.
     pub fn new_with_cells(cells: G) -> Self {
+        Self::new_with_cells_and_rule(LifeRule::CONWAY, cells)
+    }
+
+    /// Creates a new `Life` simulation with the given rule and existing cells
+    pub fn new_with_cells_and_rule(rule: LifeRule, cells: G) -> Self {
         let scratch = cells.clone();
-        Self { cells, scratch }
+        let boundary = cells.boundary();
+        Self {
+            cells,
+            scratch,
+            boundary,
+            rule,
+            generation: 0,
+            stats: StatsRecorder::default(),
+        }
     }
 }
 
@@ -36,17 +82,46 @@ impl<G: Grid> Life<G> {
         self.cells.height() as i16
     }
 
+    /// How many generations have been stepped through since this simulation was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The rule this simulation steps under
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// Changes the rule used by future steps. Existing cells are kept as-is.
+    pub fn set_rule(&mut self, rule: LifeRule) {
+        self.rule = rule;
+    }
+
+    /// How cells beyond the edges of the simulation are treated
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how cells beyond the edges of the simulation are treated.
+    ///
+    /// This overrides the [`Boundary`] of the underlying grid for this simulation only.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
     /// Checks whether the cell at `(x, y)` is **alive** or **dead**.
     ///
-    /// Out of bounds access wrap around.
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
     #[track_caller]
     pub fn get(&self, x: i16, y: i16) -> bool {
-        self.cells.get(x as Index, y as Index, 0)
+        self.cells
+            .get_with_boundary(x as Index, y as Index, 0, self.boundary)
     }
 
     /// Sets the cell at `(x, y)` to either **alive** or **dead**.
     ///
-    /// Out of bounds access wrap around.
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells outside of a bounded simulation are ignored.
     ///
     /// # Return value
     /// The previous state at this cell is returned.
@@ -68,7 +143,8 @@ impl<G: Grid> Life<G> {
     /// ```
     #[track_caller]
     pub fn set(&mut self, x: i16, y: i16, is_alive: bool) -> bool {
-        self.cells.set(x as Index, y as Index, 0, is_alive)
+        self.cells
+            .set_with_boundary(x as Index, y as Index, 0, is_alive, self.boundary)
     }
 
     pub fn cells(&self) -> &G {
@@ -85,43 +161,122 @@ impl<G: Grid> Life<G> {
 
     /// Steps the simulation once, returning the number of cells updated
     ///
+    /// When both grids are [`BitGrid`]s this evaluates 64 cells at a time on packed words,
+    /// which gives the same result as stepping each cell on its own.
+    /// When both are [`SparseGrid`](crate::SparseGrid)s, only cells next to alive cells are visited.
+    ///
+    /// # Panics
+    /// Rules with `B0` can't be simulated on [unbounded](Grid::is_unbounded) grids.
+    ///
     /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
     pub fn step(&mut self) -> u32 {
+        if self.stats.is_recording() {
+            return self.step_with_stats().changed();
+        }
+
+        self.step_cells()
+    }
+
+    /// Steps the simulation once like [`step`](Self::step), returning how the cells changed.
+    ///
+    /// This compares every cell before and after the step, so it is slower than `step`.
+    pub fn step_with_stats(&mut self) -> StepStats {
+        self.step_cells();
+        self.stats
+            .record(self.generation, &self.scratch, &self.cells)
+    }
+
+    /// Starts or stops recording the [`StepStats`] of every step into a [`TimeSeries`].
+    ///
+    /// While recording, [`step`](Self::step) is as slow as [`step_with_stats`](Self::step_with_stats).
+    /// Stopping throws away everything recorded so far.
+    pub fn record_stats(&mut self, record: bool) {
+        self.stats.set_recording(record);
+    }
+
+    /// The stats recorded since [`record_stats`](Self::record_stats) was turned on, if it is
+    pub fn time_series(&self) -> Option<&TimeSeries> {
+        self.stats.series()
+    }
+
+    /// Stops recording stats, returning everything recorded so far
+    pub fn take_time_series(&mut self) -> Option<TimeSeries> {
+        self.stats.take()
+    }
+
+    /// Steps `cells` once with the fastest method for its grid, returning the number of cells updated.
+    ///
+    /// Afterwards, `scratch` holds the previous generation.
+    fn step_cells(&mut self) -> u32 {
+        assert!(
+            !(self.rule.has_b0() && self.cells.is_unbounded()),
+            "Life can't simulate B0 rules on an unbounded grid, but was given {}",
+            self.rule
+        );
+
+        if let (Some(cells), Some(scratch)) =
+            (self.cells.as_bitgrid(), self.scratch.as_bitgrid_mut())
+        {
+            let count = step_words(cells, scratch, self.rule, self.boundary);
+            core::mem::swap(&mut self.cells, &mut self.scratch);
+            self.generation += 1;
+            return count;
+        }
+
+        #[cfg(feature = "std")]
+        if let (Some(cells), Some(scratch)) = (self.cells.as_sparse(), self.scratch.as_sparse_mut())
+        {
+            let count = step_sparse(cells, scratch, self.rule);
+            core::mem::swap(&mut self.cells, &mut self.scratch);
+            self.generation += 1;
+            return count;
+        }
+
+        self.step_generic()
+    }
+
+    /// Steps the simulation once, one cell at a time with [`Grid::get`]. This works for any grid.
+    fn step_generic(&mut self) -> u32 {
         let mut count = 0;
 
-        for y in 0..self.height() {
-            for x in 0..self.width() {
+        // Unbounded grids only hold the cells they were given, and can grow by one cell on every side each step
+        let (min, max) = self.cells.extent();
+        let pad = self.cells.is_unbounded() as Index;
+        if self.cells.is_unbounded() {
+            self.scratch.clear();
+        }
+
+        let get = |x: Index, y: Index| self.cells.get_with_boundary(x, y, 0, self.boundary);
+
+        for y in (min.y - pad)..(max.y + pad) {
+            for x in (min.x - pad)..(max.x + pad) {
                 let mut live_count = 0;
 
-                live_count += self.get(x - 1, y - 1) as u8;
-                live_count += self.get(x - 1, y + 0) as u8;
-                live_count += self.get(x - 1, y + 1) as u8;
+                live_count += get(x - 1, y - 1) as u8;
+                live_count += get(x - 1, y + 0) as u8;
+                live_count += get(x - 1, y + 1) as u8;
 
-                live_count += self.get(x + 0, y - 1) as u8;
+                live_count += get(x + 0, y - 1) as u8;
                 // Don't count itself, skip (x+0, y+0)
-                live_count += self.get(x + 0, y + 1) as u8;
+                live_count += get(x + 0, y + 1) as u8;
 
-                live_count += self.get(x + 1, y - 1) as u8;
-                live_count += self.get(x + 1, y + 0) as u8;
-                live_count += self.get(x + 1, y + 1) as u8;
+                live_count += get(x + 1, y - 1) as u8;
+                live_count += get(x + 1, y + 0) as u8;
+                live_count += get(x + 1, y + 1) as u8;
 
-                let is_alive = if self.get(x, y) {
-                    // Continues to live
-                    (live_count == 2) || (live_count == 3)
-                } else {
-                    // lives, as if by reproduction
-                    live_count == 3
-                };
+                let was_alive = get(x, y);
+                let is_alive = self.rule.next_state(was_alive, live_count);
 
-                self.scratch.set(x as Index, y as Index, 0, is_alive);
+                self.scratch.set(x, y, 0, is_alive);
 
-                if self.get(x, y) != is_alive {
+                if was_alive != is_alive {
                     count += 1;
                 }
             }
         }
 
         core::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
 
         count
     }
@@ -130,9 +285,198 @@ impl<G: Grid> Life<G> {
     pub fn clear(&mut self) {
         self.cells.clear();
     }
+
+    /// Marks all cells as **dead** and sets the [generation](Self::generation) back to `0`
+    ///
+    /// If stats are being recorded, the recorded series is emptied but recording continues.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generation = 0;
+        self.stats.clear();
+    }
+}
+
+/// Steps a whole generation of `cells` into `scratch` on packed words, returning the number of cells updated.
+///
+/// Each bit of a word is one cell. The 8 neighbors of all 64 cells in a word are summed at once with full adders,
+/// and `rule` is applied to the resulting bit-sliced counts.
+fn step_words(cells: &BitGrid, scratch: &mut BitGrid, rule: LifeRule, boundary: Boundary) -> u32 {
+    let width = cells.width() as usize;
+    let words_per_row = cells.words_per_row();
+    if words_per_row == 0 {
+        return 0;
+    }
+
+    // Valid bits in the last word of each row
+    let last_mask = match width % 64 {
+        0 => u64::MAX,
+        n => (1 << n) - 1,
+    };
+    let last_bit = (width - 1) % 64;
+
+    // Rolling window of rows y-1, y, and y+1, each with the cells just past its ends
+    let mut rows = [
+        vec![0; words_per_row],
+        vec![0; words_per_row],
+        vec![0; words_per_row],
+    ];
+    let mut edges = [(false, false); 3];
+    for (i, dy) in [-1, 0].into_iter().enumerate() {
+        edges[i] = cells.read_row_with_boundary(dy, 0, boundary, &mut rows[i]);
+    }
+
+    let mut next = vec![0; words_per_row];
+    let mut count = 0;
+
+    for y in 0..cells.height() {
+        edges[2] = cells.read_row_with_boundary(y + 1, 0, boundary, &mut rows[2]);
+
+        for k in 0..words_per_row {
+            // Shift each row so that its west and east neighbors line up with the center cell
+            let shifted = |row: &[u64], (west_edge, east_edge): (bool, bool)| -> (u64, u64) {
+                let carry_in = if k == 0 {
+                    west_edge as u64
+                } else {
+                    row[k - 1] >> 63
+                };
+                let west = (row[k] << 1) | carry_in;
+
+                let mut east = row[k] >> 1;
+                if k + 1 < words_per_row {
+                    east |= row[k + 1] << 63;
+                } else {
+                    east |= (east_edge as u64) << last_bit;
+                }
+
+                (west, east)
+            };
+
+            let (up_w, up_e) = shifted(&rows[0], edges[0]);
+            let (mid_w, mid_e) = shifted(&rows[1], edges[1]);
+            let (down_w, down_e) = shifted(&rows[2], edges[2]);
+            let (up, center, down) = (rows[0][k], rows[1][k], rows[2][k]);
+
+            // Sum the 8 neighbors into a 4-bit count: (bit3, bit2, bit1, bit0)
+            let (up_sum, up_carry) = full_add(up_w, up, up_e);
+            let (down_sum, down_carry) = full_add(down_w, down, down_e);
+            let (mid_sum, mid_carry) = (mid_w ^ mid_e, mid_w & mid_e);
+
+            let (bit0, ones_carry) = full_add(up_sum, down_sum, mid_sum);
+            let (twos_sum, twos_carry) = full_add(up_carry, down_carry, mid_carry);
+            let (bit1, twos_carry_2) = (twos_sum ^ ones_carry, twos_sum & ones_carry);
+            let (bit2, bit3) = (twos_carry ^ twos_carry_2, twos_carry & twos_carry_2);
+
+            let mut born = 0;
+            let mut survive = 0;
+            for n in 0..=8 {
+                let is_born = rule.is_born(n);
+                let survives = rule.survives(n);
+                if !is_born && !survives {
+                    continue;
+                }
+
+                let matches = |bit: u64, place: u8| if (n >> place) & 1 != 0 { bit } else { !bit };
+                let has_n =
+                    matches(bit0, 0) & matches(bit1, 1) & matches(bit2, 2) & matches(bit3, 3);
+
+                if is_born {
+                    born |= has_n;
+                }
+                if survives {
+                    survive |= has_n;
+                }
+            }
+
+            let mut alive = (center & survive) | (!center & born);
+            if k + 1 == words_per_row {
+                alive &= last_mask;
+            }
+
+            count += (alive ^ center).count_ones();
+            next[k] = alive;
+        }
+
+        scratch.write_row(y, 0, &next);
+
+        rows.rotate_left(1);
+        edges.rotate_left(1);
+    }
+
+    count
+}
+
+/// Steps a whole generation of `cells` into `scratch` by only visiting alive cells and their neighbors,
+/// returning the number of cells updated.
+#[cfg(feature = "std")]
+fn step_sparse(cells: &crate::SparseGrid, scratch: &mut crate::SparseGrid, rule: LifeRule) -> u32 {
+    // Every alive cell adds one to each of its neighbors
+    let mut neighbors: std::collections::HashMap<IVec3, u8> = Default::default();
+    for pos in cells.iter().filter(|pos| pos.z == 0) {
+        neighbors.entry(pos).or_insert(0);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) {
+                    *neighbors.entry(pos + IVec3::new(dx, dy, 0)).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // Other layers aren't stepped, so carry them over as-is
+    scratch.clear();
+    for pos in cells.iter().filter(|pos| pos.z != 0) {
+        scratch.set(pos.x, pos.y, pos.z, true);
+    }
+
+    let mut count = 0;
+    for (pos, live_count) in neighbors {
+        let was_alive = cells.get(pos.x, pos.y, pos.z);
+        let is_alive = rule.next_state(was_alive, live_count);
+        if is_alive {
+            scratch.set(pos.x, pos.y, pos.z, true);
+        }
+        count += (was_alive != is_alive) as u32;
+    }
+
+    count
+}
+
+/// Adds three bits in each lane, returning `(sum, carry)`
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
 }
 
 impl Life<crate::BitGrid> {
+    /// Creates a new `Life` simulation from a pattern in the RLE format. See [`Rle`](crate::Rle) for details.
+    ///
+    /// The simulation is sized to the pattern and uses the rule from its header, or Conway's Game of Life if it has none.
+    /// Returns `None` if the pattern or its rule can't be parsed.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{Life, LifeRule};
+    /// let mut life = Life::from_rle("x = 3, y = 1, rule = B36/S23\n3o!").unwrap();
+    /// assert_eq!(life.rule(), LifeRule::HIGHLIFE);
+    /// assert_eq!((life.width(), life.height()), (3, 1));
+    /// ```
+    pub fn from_rle(text: &str) -> Option<Self> {
+        let rle = crate::Rle::parse(text)?;
+        let rule = match &rle.rule {
+            Some(rule) => LifeRule::parse(rule)?,
+            None => LifeRule::CONWAY,
+        };
+
+        Some(Self::new_with_cells_and_rule(rule, rle.grid))
+    }
+
+    /// Writes the current cells and rule in the RLE format
+    pub fn to_rle(&self) -> String {
+        let mut rle = crate::Rle::new(self.cells.clone());
+        rle.rule = Some(self.rule.to_string());
+        rle.to_string()
+    }
+
     /// Set all cells to **alive** or **dead** using the provided rng.
     pub fn clear_random(&mut self, rng: &mut impl rand::Rng) {
         let bytes: &mut [u8] = self.cells.as_mut_bytes();
@@ -140,6 +484,7 @@ impl Life<crate::BitGrid> {
             let rand_bytes = rng.next_u32().to_le_bytes();
             chunk.copy_from_slice(&rand_bytes[..chunk.len()]);
         }
+        self.cells.clear_padding();
     }
 }
 
@@ -158,17 +503,7 @@ impl<G: Grid + Clone> Life<G> {
     /// Where the top left is `(x, y)`.
     #[track_caller]
     pub fn write_right_glider(&mut self, x: i16, y: i16) {
-        self.set(x + 0, y + 0, false);
-        self.set(x + 1, y + 0, true);
-        self.set(x + 2, y + 0, false);
-
-        self.set(x + 0, y + 1, false);
-        self.set(x + 1, y + 1, false);
-        self.set(x + 2, y + 1, true);
-
-        self.set(x + 0, y + 2, true);
-        self.set(x + 1, y + 2, true);
-        self.set(x + 2, y + 2, true);
+        self.write_glider(x, y, Transform::Identity);
     }
 
     /// Writes left-facing glider with its corner at `(x, y)`
@@ -177,24 +512,24 @@ impl<G: Grid + Clone> Life<G> {
     /// A left-facing glider looks like this:
     /// ```txt
     /// .O.
-    /// O.
+    /// O..
     /// OOO
     /// ```
     ///
     /// Where the top left is `(x, y)`.
     #[track_caller]
     pub fn write_left_glider(&mut self, x: i16, y: i16) {
-        self.set(x + 0, y + 0, false);
-        self.set(x + 1, y + 0, true);
-        self.set(x + 2, y + 0, false);
-
-        self.set(x + 0, y + 1, true);
-        self.set(x + 1, y + 1, false);
-        self.set(x + 2, y + 1, false);
+        self.write_glider(x, y, Transform::FlipX);
+    }
 
-        self.set(x + 0, y + 2, true);
-        self.set(x + 1, y + 2, true);
-        self.set(x + 2, y + 2, true);
+    /// Overwrites the 3x3 square at `(x, y)` with a transformed right-facing glider
+    fn write_glider(&mut self, x: i16, y: i16, transform: Transform) {
+        let glider = BitGrid::new_with_fn(3, 3, 1, |x, y, _| {
+            matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2))
+        });
+        let at = IVec3::new(x as Index, y as Index, 0);
+        self.cells
+            .stamp_with(&glider, at, transform, Blend::Overwrite, self.boundary);
     }
 }
 
@@ -221,6 +556,8 @@ impl<G: Grid + Clone> Life<G> {
 mod test {
     use super::*;
 
+    use rstest::*;
+
     #[test]
     fn check_square_lives() {
         let mut life: Life = Life::new(5, 5);
@@ -246,6 +583,221 @@ mod test {
         assert_eq!(updated, 0);
     }
 
+    #[test]
+    fn check_highlife_replicator() {
+        // The HighLife replicator copies itself; under Conway's rules it doesn't
+        // ..OOO
+        // .O..O
+        // O...O
+        // O..O.
+        // OOO..
+        let replicator = [
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (1, 1),
+            (4, 1),
+            (0, 2),
+            (4, 2),
+            (0, 3),
+            (3, 3),
+            (0, 4),
+            (1, 4),
+            (2, 4),
+        ];
+
+        let mut highlife: Life = Life::new_with_rule(LifeRule::HIGHLIFE, 64, 64);
+        let mut conway: Life = Life::new(64, 64);
+        for (x, y) in replicator {
+            highlife.set(x + 30, y + 30, true);
+            conway.set(x + 30, y + 30, true);
+        }
+
+        for _ in 0..12 {
+            highlife.step();
+            conway.step();
+        }
+
+        // After 12 generations the replicator has made two copies of itself
+        let population = |life: &Life| alive_cells(life).len();
+        assert_eq!(population(&highlife), 24);
+        assert_ne!(population(&conway), population(&highlife));
+    }
+
+    #[test]
+    fn check_seeds_never_survive() {
+        let mut life: Life = Life::new_with_rule(LifeRule::parse("B2/S").unwrap(), 16, 16);
+        life.set(4, 4, true);
+        life.set(5, 4, true);
+
+        // Each cell has one neighbor, so both die, and the 4 cells above and below are born
+        assert_eq!(life.step(), 6);
+        assert_eq!(alive_cells(&life), [(4, 3), (5, 3), (4, 5), (5, 5)]);
+    }
+
+    #[test]
+    fn check_b0_on_torus() {
+        // B0 without S8 strobes: empty space fills up, then a full board dies
+        let mut life: Life = Life::new_with_rule(LifeRule::parse("B0/S").unwrap(), 8, 8);
+        assert_eq!(life.step(), 64);
+        assert_eq!(alive_cells(&life).len(), 64);
+
+        assert_eq!(life.step(), 64);
+        assert_eq!(alive_cells(&life).len(), 0);
+    }
+
+    #[test]
+    fn check_clear() {
+        let mut life: Life = Life::new(5, 5);
+        life.write_right_glider(1, 1);
+        life.clear();
+        assert_eq!(alive_cells(&life), []);
+    }
+
+    #[rstest]
+    #[case::conway_wrap(LifeRule::CONWAY, Boundary::Wrap, 64, 64)]
+    #[case::conway_odd_size(LifeRule::CONWAY, Boundary::Wrap, 37, 23)]
+    #[case::conway_multi_word(LifeRule::CONWAY, Boundary::Wrap, 200, 20)]
+    #[case::conway_dead(LifeRule::CONWAY, Boundary::Dead, 70, 31)]
+    #[case::conway_alive(LifeRule::CONWAY, Boundary::Alive, 65, 17)]
+    #[case::conway_reflect(LifeRule::CONWAY, Boundary::Reflect, 128, 9)]
+    #[case::conway_klein(LifeRule::CONWAY, Boundary::Klein, 33, 40)]
+    #[case::highlife(LifeRule::HIGHLIFE, Boundary::Wrap, 100, 50)]
+    #[case::day_and_night(LifeRule::DAY_AND_NIGHT, Boundary::Klein, 90, 30)]
+    #[case::seeds(LifeRule::SEEDS, Boundary::Dead, 63, 40)]
+    #[case::b0(
+        LifeRule::from_masks(0b0_0000_0001, 0b1_0000_0000),
+        Boundary::Wrap,
+        66,
+        12
+    )]
+    #[case::tiny(LifeRule::CONWAY, Boundary::Wrap, 1, 1)]
+    #[case::narrow(LifeRule::CONWAY, Boundary::Wrap, 2, 5)]
+    fn check_word_step_matches_generic(
+        #[case] rule: LifeRule,
+        #[case] boundary: Boundary,
+        #[case] width: usize,
+        #[case] height: usize,
+    ) {
+        use rand::{rngs::SmallRng, SeedableRng};
+        let mut rng = SmallRng::seed_from_u64(0x5eed + width as u64 * height as u64);
+
+        let mut fast: Life = Life::new_with_rule(rule, width, height);
+        fast.set_boundary(boundary);
+        fast.clear_random(&mut rng);
+        let mut generic = fast.clone();
+
+        for generation in 0..32 {
+            let fast_count = fast.step();
+            let generic_count = generic.step_generic();
+
+            assert_eq!(
+                fast.cells(),
+                generic.cells(),
+                "Cells diverged in generation {generation}"
+            );
+            assert_eq!(
+                fast_count, generic_count,
+                "Update count diverged in generation {generation}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_rle_round_trip() {
+        let text = "x = 5, y = 4, rule = B36/S23\nbo2bo$o$o3bo$4o!\n";
+        let mut life = Life::from_rle(text).unwrap();
+        assert_eq!(life.rule(), LifeRule::HIGHLIFE);
+        assert_eq!(alive_cells(&life).len(), 9);
+        assert_eq!(life.to_rle(), text);
+
+        life.set_rule(LifeRule::SEEDS);
+        assert!(life.to_rle().starts_with("x = 5, y = 4, rule = B2/S\n"));
+
+        assert!(Life::from_rle("x = 1, y = 1, rule = Wireworld\no!").is_none());
+    }
+
+    fn alive_cells(life: &Life) -> Vec<(i16, i16)> {
+        let mut cells = vec![];
+        for y in 0..life.height() {
+            for x in 0..life.width() {
+                if life.get(x, y) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn check_spinner_on_edge_by_boundary() {
+        // A vertical spinner hugging the left edge
+        let spinner = [(0, 1), (0, 2), (0, 3)];
+
+        let mut life: Life = Life::new(5, 5);
+        for (x, y) in spinner {
+            life.set(x, y, true);
+        }
+        life.step();
+        assert_eq!(alive_cells(&life), [(0, 2), (1, 2), (4, 2)]);
+
+        let mut life: Life = Life::new(5, 5);
+        life.set_boundary(Boundary::Dead);
+        for (x, y) in spinner {
+            life.set(x, y, true);
+        }
+        life.step();
+        assert_eq!(alive_cells(&life), [(0, 2), (1, 2)]);
+        life.step();
+        assert_eq!(alive_cells(&life), []);
+    }
+
+    #[test]
+    fn check_glider_does_not_leak_through_dead_boundary() {
+        let mut life: Life =
+            Life::new_with_cells(BitGrid::new(8, 8, 1).with_boundary(Boundary::Dead));
+        assert_eq!(life.boundary(), Boundary::Dead);
+        life.write_right_glider(2, 2);
+
+        for _ in 0..32 {
+            life.step();
+        }
+
+        // The glider crashes into the bottom right corner and leaves a block behind
+        assert_eq!(alive_cells(&life), [(6, 6), (7, 6), (6, 7), (7, 7)]);
+    }
+
+    #[test]
+    fn check_reflect_matches_mirrored_torus() {
+        // Reflecting edges behave like a torus twice as large holding mirrored copies of the grid
+        let (w, h) = (7, 6);
+        let seed = |x: i16, y: i16| (x * 7 + y * 13 + x * y) % 5 < 2;
+
+        let mut bounded: Life = Life::new(w as usize, h as usize);
+        bounded.set_boundary(Boundary::Reflect);
+        let mut torus: Life = Life::new(2 * w as usize, 2 * h as usize);
+        for y in 0..h {
+            for x in 0..w {
+                let alive = seed(x, y);
+                bounded.set(x, y, alive);
+                torus.set(x, y, alive);
+                torus.set(2 * w - 1 - x, y, alive);
+                torus.set(x, 2 * h - 1 - y, alive);
+                torus.set(2 * w - 1 - x, 2 * h - 1 - y, alive);
+            }
+        }
+
+        for _ in 0..20 {
+            bounded.step();
+            torus.step();
+            for y in 0..h {
+                for x in 0..w {
+                    assert_eq!(bounded.get(x, y), torus.get(x, y), "Mismatch at ({x}, {y})");
+                }
+            }
+        }
+    }
+
     #[test]
     fn check_spinner_spins() {
         let mut life: Life = Life::new(5, 5);
@@ -271,4 +823,53 @@ mod test {
         // So 4.
         assert_eq!(updated, 4);
     }
+
+    #[test]
+    fn check_step_with_stats() {
+        let mut life: Life = Life::new(5, 5);
+        for (x, y) in [(1, 1), (1, 2), (1, 3)] {
+            life.set(x, y, true);
+        }
+
+        let stats = life.step_with_stats();
+        assert_eq!(
+            stats,
+            StepStats {
+                generation: 1,
+                births: 2,
+                deaths: 2,
+                population: 3,
+                changed_bounds: Some((IVec3::new(0, 1, 0), IVec3::new(2, 3, 0))),
+            }
+        );
+        assert_eq!(life.step_with_stats().generation, 2);
+    }
+
+    #[test]
+    fn check_recorded_stats_match_step() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let mut life: Life = Life::new(30, 30);
+        life.clear_random(&mut SmallRng::seed_from_u64(5));
+        let mut twin = life.clone();
+
+        life.record_stats(true);
+        let changed: Vec<u32> = (0..20).map(|_| life.step()).collect();
+
+        let series = life.take_time_series().unwrap();
+        let expected: TimeSeries = (0..20).map(|_| twin.step_with_stats()).collect();
+        assert_eq!(series, expected);
+        assert_eq!(
+            series.iter().map(StepStats::changed).collect::<Vec<_>>(),
+            changed
+        );
+        assert_eq!(
+            series.last().unwrap().population,
+            life.cells().count_set() as u64
+        );
+
+        // Taking the series stops recording
+        life.step();
+        assert!(life.time_series().is_none());
+    }
 }
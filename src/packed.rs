@@ -0,0 +1,446 @@
+use crate::grid::{flat_index, Resolved};
+use crate::prelude::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// A dense [`Grid`] of multi-state cells, packed at 1, 2, 4, or 8 bits per cell.
+///
+/// This is the multi-state counterpart to [`BitGrid`]: cells are laid out in the same order and out of bounds access
+/// is resolved by the same [`Boundary`] rules. The cell type `C` decides how many bits each cell takes,
+/// rounded up to the next power of two so a cell never straddles a byte.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// let mut grid: PackedGrid<u8> = PackedGrid::new_with_bits(4, 4, 1, 2);
+/// grid.set(1, 2, 0, 3);
+///
+/// assert_eq!(grid.get(1, 2, 0), 3);
+/// assert_eq!(grid.count(3), 1);
+/// assert_eq!(grid.as_bytes().len(), 4);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct PackedGrid<C: Cell = u8> {
+    buf: Vec<u8>,
+    bits: u8,
+    width: Index,
+    height: Index,
+    depth: Index,
+    boundary: Boundary,
+    _cell: PhantomData<C>,
+}
+
+impl<C: Cell> core::fmt::Debug for PackedGrid<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PackedGrid")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("depth", &self.depth)
+            .field("bits", &self.bits)
+            .field("boundary", &self.boundary)
+            .finish()
+    }
+}
+
+/// Basic Usage
+impl<C: Cell> PackedGrid<C> {
+    /// Creates a grid where every cell is [`C::DEAD`](Cell::DEAD), using the fewest bits that fit `C`.
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self::new_with_bits(width, height, depth, C::BITS.next_power_of_two())
+    }
+
+    /// Creates a grid where every cell is [`C::DEAD`](Cell::DEAD), with `bits` bits per cell.
+    ///
+    /// `bits` may be smaller than [`C::BITS`](Cell::BITS) when only a few states are used, as with a `u8` that only
+    /// counts up to 3. Cells are truncated to their low `bits` bits when written.
+    ///
+    /// # Panics
+    /// If `bits` is not one of 1, 2, 4, or 8.
+    #[track_caller]
+    pub fn new_with_bits(width: usize, height: usize, depth: usize, bits: u8) -> Self {
+        assert!(
+            matches!(bits, 1 | 2 | 4 | 8),
+            "PackedGrid cells must be 1, 2, 4, or 8 bits, not {bits}"
+        );
+
+        let mut grid = Self {
+            buf: vec![0; (width * height * depth * bits as usize).div_ceil(8)],
+            bits,
+            width: width as Index,
+            height: height as Index,
+            depth: depth as Index,
+            boundary: Boundary::Wrap,
+            _cell: PhantomData,
+        };
+        if C::DEAD.to_bits() != 0 {
+            grid.fill(C::DEAD);
+        }
+        grid
+    }
+
+    pub fn new_with_fn<F>(width: usize, height: usize, depth: usize, mut func: F) -> Self
+    where
+        F: FnMut(Index, Index, Index) -> C,
+    {
+        let mut grid = Self::new(width, height, depth);
+        for z in 0..grid.depth() {
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    grid.set(x, y, z, func(x, y, z));
+                }
+            }
+        }
+        grid
+    }
+
+    /// Builder-style version of [`set_boundary`](Self::set_boundary)
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn width(&self) -> Index {
+        self.width
+    }
+
+    pub fn height(&self) -> Index {
+        self.height
+    }
+
+    pub fn depth(&self) -> Index {
+        self.depth
+    }
+
+    pub fn dims(&self) -> IVec3 {
+        IVec3::new(self.width, self.height, self.depth)
+    }
+
+    /// Number of bits each cell takes in [`as_bytes`](Self::as_bytes)
+    pub fn bits_per_cell(&self) -> u8 {
+        self.bits
+    }
+
+    /// How out of bounds coordinates are treated by [`get`](Self::get) and [`set`](Self::set).
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how out of bounds coordinates are treated. New grids [`Wrap`](Boundary::Wrap).
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    /// Number of cells in the state `state`
+    pub fn count(&self, state: C) -> usize {
+        self.iter().filter(|&(_, cell)| cell == state).count()
+    }
+
+    /// Whether every cell is [`C::DEAD`](Cell::DEAD)
+    pub fn is_empty(&self) -> bool {
+        self.iter().all(|(_, cell)| cell == C::DEAD)
+    }
+
+    /// Iterates over every cell and its position, in memory order.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec3, C)> + '_ {
+        let dims = self.dims();
+        (0..dims.z).flat_map(move |z| {
+            (0..dims.y).flat_map(move |y| {
+                (0..dims.x).map(move |x| {
+                    let pos = IVec3::new(x, y, z);
+                    (pos, self.read(flat_index(dims, pos)))
+                })
+            })
+        })
+    }
+
+    /// Checks the cell at `(x, y, z)`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    #[track_caller]
+    pub fn get(&self, x: Index, y: Index, z: Index) -> C {
+        match self.boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => self.read(flat_index(self.dims(), pos)),
+            Resolved::Constant(elem) => C::from_alive(elem),
+        }
+    }
+
+    /// Sets the cell at `(x, y, z)`, returning its previous state.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells that are outside of the grid entirely are ignored.
+    #[track_caller]
+    pub fn set(&mut self, x: Index, y: Index, z: Index, elem: C) -> C {
+        match self.boundary.resolve(self.dims(), IVec3::new(x, y, z)) {
+            Resolved::Cell(pos) => {
+                let i = flat_index(self.dims(), pos);
+                let old = self.read(i);
+                self.write(i, elem);
+                old
+            }
+            Resolved::Constant(old) => C::from_alive(old),
+        }
+    }
+
+    /// Sets every cell to `elem`
+    pub fn fill(&mut self, elem: C) {
+        // Repeat the cell's bit pattern across a whole byte
+        let mut byte = elem.to_bits() & self.cell_mask();
+        let mut width = self.bits;
+        while width < 8 {
+            byte |= byte << width;
+            width *= 2;
+        }
+        self.buf.fill(byte);
+
+        // Keep the padding bits past the last cell clear
+        let used_bits =
+            (self.dims().x * self.dims().y * self.dims().z) as usize * self.bits as usize % 8;
+        if let Some(last) = self.buf.last_mut() {
+            if used_bits != 0 {
+                *last &= (1 << used_bits) - 1;
+            }
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Resizes the grid to `new_dims`, placing existing cells according to `anchor`.
+    ///
+    /// Cells that land outside of the new dimensions are dropped, and newly exposed cells are [`C::DEAD`](Cell::DEAD).
+    pub fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        let (bits, boundary) = (self.bits, self.boundary);
+        crate::grid::resize_into(self, new_dims, anchor, |dims| {
+            Self::new_with_bits(dims.x as usize, dims.y as usize, dims.z as usize, bits)
+                .with_boundary(boundary)
+        });
+    }
+
+    fn cell_mask(&self) -> u8 {
+        (((1_u16) << self.bits) - 1) as u8
+    }
+
+    /// Byte and bit shift of the cell at flat index `i`
+    fn locate(&self, i: usize) -> (usize, u8) {
+        let bit = i * self.bits as usize;
+        (bit / 8, (bit % 8) as u8)
+    }
+
+    fn read(&self, i: usize) -> C {
+        let (byte, shift) = self.locate(i);
+        C::from_bits((self.buf[byte] >> shift) & self.cell_mask())
+    }
+
+    fn write(&mut self, i: usize, elem: C) {
+        let (byte, shift) = self.locate(i);
+        let mask = self.cell_mask() << shift;
+        self.buf[byte] = (self.buf[byte] & !mask) | ((elem.to_bits() << shift) & mask);
+    }
+}
+
+/// Conversions to and from boolean grids
+impl<C: Cell> PackedGrid<C> {
+    /// Copies `grid`, mapping set cells to `alive` and unset cells to [`C::DEAD`](Cell::DEAD).
+    pub fn from_bitgrid(grid: &BitGrid, alive: C) -> Self {
+        Self::new_with_fn(
+            grid.width() as usize,
+            grid.height() as usize,
+            grid.depth() as usize,
+            |x, y, z| if grid.get(x, y, z) { alive } else { C::DEAD },
+        )
+        .with_boundary(grid.boundary())
+    }
+
+    /// Copies this grid into a [`BitGrid`], setting the cells where `is_set` returns true.
+    pub fn to_bitgrid(&self, mut is_set: impl FnMut(C) -> bool) -> BitGrid {
+        BitGrid::new_with_fn(
+            self.width() as usize,
+            self.height() as usize,
+            self.depth() as usize,
+            |x, y, z| is_set(self.get(x, y, z)),
+        )
+        .with_boundary(self.boundary())
+    }
+}
+
+impl<C: Cell> GridNew<C> for PackedGrid<C> {
+    fn new(dims: IVec3) -> Self {
+        Self::new(dims.x as usize, dims.y as usize, dims.z as usize)
+    }
+}
+
+impl<C: Cell> Grid<C> for PackedGrid<C> {
+    fn dims(&self) -> IVec3 {
+        self.dims()
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.boundary()
+    }
+
+    #[track_caller]
+    fn get(&self, x: Index, y: Index, z: Index) -> C {
+        self.get(x, y, z)
+    }
+
+    #[track_caller]
+    fn set(&mut self, x: Index, y: Index, z: Index, elem: C) -> C {
+        self.set(x, y, z, elem)
+    }
+
+    fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        self.resize_with_anchor(new_dims, anchor)
+    }
+
+    fn fill(&mut self, elem: C) {
+        self.fill(elem)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::one_bit(1)]
+    #[case::two_bits(2)]
+    #[case::four_bits(4)]
+    #[case::eight_bits(8)]
+    fn check_get_set_round_trip(#[case] bits: u8) {
+        let states = 1_u16 << bits;
+        let pick = |x: Index, y: Index, z: Index| ((x * 7 + y * 3 + z * 5) as u16 % states) as u8;
+
+        let mut grid: PackedGrid<u8> = PackedGrid::new_with_bits(13, 5, 2, bits);
+        assert_eq!(
+            grid.as_bytes().len(),
+            (13 * 5 * 2 * bits as usize).div_ceil(8)
+        );
+
+        for (pos, _) in PackedGrid::<u8>::new(13, 5, 2).iter() {
+            let old = grid.set(pos.x, pos.y, pos.z, pick(pos.x, pos.y, pos.z));
+            assert_eq!(old, 0);
+        }
+        for (pos, cell) in grid.iter() {
+            assert_eq!(cell, pick(pos.x, pos.y, pos.z), "at {pos:?}");
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+    enum Tri {
+        #[default]
+        Off,
+        On,
+        Dying,
+    }
+
+    impl Cell for Tri {
+        const BITS: u8 = 2;
+        const DEAD: Self = Tri::Off;
+        const ALIVE: Self = Tri::On;
+
+        fn to_bits(self) -> u8 {
+            self as u8
+        }
+
+        fn from_bits(bits: u8) -> Self {
+            match bits {
+                1 => Tri::On,
+                2 => Tri::Dying,
+                _ => Tri::Off,
+            }
+        }
+    }
+
+    #[test]
+    fn check_enum_cells() {
+        let mut grid: PackedGrid<Tri> = PackedGrid::new(5, 5, 1);
+        assert_eq!(grid.bits_per_cell(), 2);
+        assert!(grid.is_empty());
+
+        grid.set(1, 1, 0, Tri::On);
+        grid.set(2, 1, 0, Tri::Dying);
+        grid.set(3, 1, 0, Tri::Dying);
+
+        assert_eq!(grid.count(Tri::On), 1);
+        assert_eq!(grid.count(Tri::Dying), 2);
+        assert_eq!(grid.count(Tri::Off), 22);
+
+        // Wraps like a BitGrid
+        assert_eq!(grid.get(-3, 6, 0), Tri::Dying);
+    }
+
+    #[rstest]
+    #[case::wrap(Boundary::Wrap, 3)]
+    #[case::dead(Boundary::Dead, 0)]
+    #[case::alive(Boundary::Alive, 1)]
+    #[case::reflect(Boundary::Reflect, 2)]
+    fn check_boundary(#[case] boundary: Boundary, #[case] expected: u8) {
+        let mut grid: PackedGrid<u8> = PackedGrid::new(3, 1, 1).with_boundary(boundary);
+        grid.set(0, 0, 0, 2);
+        grid.set(2, 0, 0, 3);
+
+        assert_eq!(grid.get(-1, 0, 0), expected);
+
+        // Writes outside of a constant boundary are dropped
+        grid.set(-1, 0, 0, 7);
+        let kept = matches!(boundary, Boundary::Wrap | Boundary::Reflect);
+        assert_eq!(grid.count(7), usize::from(kept));
+    }
+
+    #[rstest]
+    #[case::bits_1(1, 11)]
+    #[case::bits_2(2, 7)]
+    #[case::bits_4(4, 3)]
+    fn check_fill_keeps_padding_clear(#[case] bits: u8, #[case] width: usize) {
+        let mut grid: PackedGrid<u8> = PackedGrid::new_with_bits(width, 1, 1, bits);
+        let full = ((1_u16 << bits) - 1) as u8;
+        grid.fill(full);
+
+        assert_eq!(grid.count(full), width);
+        let used = width * bits as usize % 8;
+        assert_eq!(grid.as_bytes().last().unwrap() >> used, 0);
+
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn check_bitgrid_round_trip() {
+        let bits = BitGrid::new_with_fn(9, 4, 1, |x, y, _| (x + y) % 3 == 0);
+        let packed: PackedGrid<u8> = PackedGrid::from_bitgrid(&bits, 5);
+
+        assert_eq!(packed.count(5), bits.count_set());
+        assert_eq!(packed.to_bitgrid(|cell| cell == 5), bits);
+    }
+
+    #[test]
+    fn check_resize_keeps_cells() {
+        let mut grid: PackedGrid<u8> =
+            PackedGrid::new_with_fn(2, 2, 1, |x, y, _| (1 + x + 2 * y) as u8);
+        grid.resize_with_anchor(IVec3::new(4, 4, 1), Anchor::Center);
+
+        assert_eq!(grid.bits_per_cell(), 8);
+        assert_eq!(
+            [
+                grid.get(1, 1, 0),
+                grid.get(2, 1, 0),
+                grid.get(1, 2, 0),
+                grid.get(2, 2, 0)
+            ],
+            [1, 2, 3, 4]
+        );
+        assert_eq!(grid.count(0), 12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_invalid_bits_panics() {
+        let _: PackedGrid<u8> = PackedGrid::new_with_bits(4, 4, 1, 3);
+    }
+}
@@ -0,0 +1,550 @@
+use crate::prelude::*;
+use crate::Rle;
+
+use core::fmt;
+
+/// A well-known pattern, embedded in the crate as RLE.
+///
+/// Each pattern knows the rule it was found in, and how it behaves under that rule:
+/// its [`kind`](Self::kind), [`period`](Self::period), how far it moves each period ([`offset`](Self::offset)),
+/// and for methuselahs, how long it takes to settle down ([`lifespan`](Self::lifespan)).
+///
+/// Spaceships face the way they usually do on the LifeWiki, so most of them fly left.
+/// Use [`Grid::stamp`] with a [`Transform`] to point them somewhere else.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// # use simulations::{Life, Pattern};
+/// let glider = Pattern::from_name("glider").unwrap();
+/// assert_eq!(glider.period(), Some(4));
+/// assert_eq!(glider.offset(), IVec3::new(1, 1, 0));
+///
+/// let mut life: Life = Life::new(32, 32);
+/// life.cells_mut().stamp(&Pattern::Pulsar.grid(), IVec3::new(8, 8, 0), Transform::Identity);
+/// assert_eq!(life.cells().count_set(), 48);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Pattern {
+    // Still lifes
+    Block,
+    Beehive,
+    Loaf,
+    Boat,
+    Tub,
+    Ship,
+
+    // Oscillators
+    Blinker,
+    Toad,
+    Beacon,
+    Pulsar,
+    Pentadecathlon,
+
+    // Spaceships
+    Glider,
+    Lwss,
+    Mwss,
+    Hwss,
+
+    // Guns
+    GosperGliderGun,
+
+    // Methuselahs
+    RPentomino,
+    Acorn,
+    Diehard,
+
+    // Elementry
+    /// A single cell, which grows into chaos under rule 30
+    Rule30Seed,
+
+    /// A single cell, which grows into a Sierpiński triangle under rule 90
+    Rule90Seed,
+
+    /// A single car in rule 184's traffic model, which drives right forever
+    Rule184Car,
+}
+
+/// What a [`Pattern`] does when left to run
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PatternKind {
+    /// Never changes
+    StillLife,
+
+    /// Returns to its starting state every period, in the same place
+    Oscillator,
+
+    /// Returns to its starting state every period, moved by its offset
+    Spaceship,
+
+    /// Returns to its starting state every period, after firing off a spaceship
+    Gun,
+
+    /// A small pattern that takes a long time to settle down
+    Methuselah,
+
+    /// A starting point for a 1D rule, which grows without repeating
+    Seed,
+}
+
+/// The rule a [`Pattern`] is meant to be run under
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PatternRule {
+    /// A 2D rule for [`Life`](crate::Life)
+    Life(LifeRule),
+
+    /// A 1D rule for [`Elementry`](crate::Elementry)
+    Elementry(u8),
+}
+
+struct Info {
+    name: &'static str,
+    kind: PatternKind,
+    rule: PatternRule,
+    period: Option<u64>,
+    offset: (Index, Index),
+    lifespan: Option<(u64, u64)>,
+    rle: &'static str,
+}
+
+/// Whether two names match, ignoring case, spaces, and punctuation
+fn same_name(a: &str, b: &str) -> bool {
+    let letters = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<alloc::string::String>()
+    };
+    letters(a) == letters(b)
+}
+
+const CONWAY: PatternRule = PatternRule::Life(LifeRule::CONWAY);
+
+impl Pattern {
+    /// Every pattern in the library
+    pub const ALL: [Self; 22] = [
+        Self::Block,
+        Self::Beehive,
+        Self::Loaf,
+        Self::Boat,
+        Self::Tub,
+        Self::Ship,
+        Self::Blinker,
+        Self::Toad,
+        Self::Beacon,
+        Self::Pulsar,
+        Self::Pentadecathlon,
+        Self::Glider,
+        Self::Lwss,
+        Self::Mwss,
+        Self::Hwss,
+        Self::GosperGliderGun,
+        Self::RPentomino,
+        Self::Acorn,
+        Self::Diehard,
+        Self::Rule30Seed,
+        Self::Rule90Seed,
+        Self::Rule184Car,
+    ];
+
+    /// Finds a pattern by its [`name`](Self::name), ignoring case, spaces, and punctuation.
+    ///
+    /// Spaceships can also be found by their abbreviations, like `"LWSS"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|pattern| {
+            same_name(pattern.name(), name) || same_name(&alloc::format!("{pattern:?}"), name)
+        })
+    }
+
+    /// The pattern's name, as it appears on the LifeWiki
+    pub fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    pub fn kind(self) -> PatternKind {
+        self.info().kind
+    }
+
+    /// The rule this pattern behaves as described under
+    pub fn rule(self) -> PatternRule {
+        self.info().rule
+    }
+
+    /// Number of generations before the pattern repeats. `None` for methuselahs and seeds, which don't.
+    ///
+    /// For guns, this is how often a spaceship is fired.
+    pub fn period(self) -> Option<u64> {
+        self.info().period
+    }
+
+    /// How far the pattern moves every [`period`](Self::period), in the same form as [`Cycle::offset`](crate::Cycle::offset).
+    ///
+    /// Its velocity is `offset / period`, so a glider's offset of `(1, 1)` every `4` generations is `c/4` diagonally.
+    /// This is zero for everything but spaceships.
+    pub fn offset(self) -> IVec3 {
+        let (x, y) = self.info().offset;
+        IVec3::new(x, y, 0)
+    }
+
+    /// For methuselahs, the generation they settle down at, and their population from then on.
+    ///
+    /// These are measured on an unbounded plane, so escaping gliders are counted.
+    pub fn lifespan(self) -> Option<(u64, u64)> {
+        self.info().lifespan
+    }
+
+    /// The pattern in RLE, including its name and rule. See [`Rle`] for details.
+    pub fn rle(self) -> &'static str {
+        self.info().rle
+    }
+
+    /// The cells of the pattern, in a grid just big enough to hold them
+    pub fn grid(self) -> BitGrid {
+        Rle::parse(self.rle())
+            .expect("Every built-in pattern is valid RLE")
+            .grid
+    }
+
+    fn info(self) -> &'static Info {
+        match self {
+            Self::Block => &Info {
+                name: "Block",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Block\nx = 2, y = 2, rule = B3/S23\n2o$2o!",
+            },
+            Self::Beehive => &Info {
+                name: "Beehive",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Beehive\nx = 4, y = 3, rule = B3/S23\nb2o$o2bo$b2o!",
+            },
+            Self::Loaf => &Info {
+                name: "Loaf",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Loaf\nx = 4, y = 4, rule = B3/S23\nb2o$o2bo$bobo$2bo!",
+            },
+            Self::Boat => &Info {
+                name: "Boat",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Boat\nx = 3, y = 3, rule = B3/S23\n2o$obo$bo!",
+            },
+            Self::Tub => &Info {
+                name: "Tub",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Tub\nx = 3, y = 3, rule = B3/S23\nbo$obo$bo!",
+            },
+            Self::Ship => &Info {
+                name: "Ship",
+                kind: PatternKind::StillLife,
+                rule: CONWAY,
+                period: Some(1),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Ship\nx = 3, y = 3, rule = B3/S23\n2o$obo$b2o!",
+            },
+            Self::Blinker => &Info {
+                name: "Blinker",
+                kind: PatternKind::Oscillator,
+                rule: CONWAY,
+                period: Some(2),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Blinker\nx = 3, y = 1, rule = B3/S23\n3o!",
+            },
+            Self::Toad => &Info {
+                name: "Toad",
+                kind: PatternKind::Oscillator,
+                rule: CONWAY,
+                period: Some(2),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Toad\nx = 4, y = 2, rule = B3/S23\nb3o$3o!",
+            },
+            Self::Beacon => &Info {
+                name: "Beacon",
+                kind: PatternKind::Oscillator,
+                rule: CONWAY,
+                period: Some(2),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Beacon\nx = 4, y = 4, rule = B3/S23\n2o$2o$2b2o$2b2o!",
+            },
+            Self::Pulsar => &Info {
+                name: "Pulsar",
+                kind: PatternKind::Oscillator,
+                rule: CONWAY,
+                period: Some(3),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Pulsar\nx = 13, y = 13, rule = B3/S23\n\
+                      2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+                      o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+            },
+            Self::Pentadecathlon => &Info {
+                name: "Pentadecathlon",
+                kind: PatternKind::Oscillator,
+                rule: CONWAY,
+                period: Some(15),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Pentadecathlon\nx = 10, y = 3, rule = B3/S23\n2bo4bo$2ob4ob2o$2bo4bo!",
+            },
+            Self::Glider => &Info {
+                name: "Glider",
+                kind: PatternKind::Spaceship,
+                rule: CONWAY,
+                period: Some(4),
+                offset: (1, 1),
+                lifespan: None,
+                rle: "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!",
+            },
+            Self::Lwss => &Info {
+                name: "Lightweight spaceship",
+                kind: PatternKind::Spaceship,
+                rule: CONWAY,
+                period: Some(4),
+                offset: (-2, 0),
+                lifespan: None,
+                rle: "#N Lightweight spaceship\nx = 5, y = 4, rule = B3/S23\nbo2bo$o$o3bo$4o!",
+            },
+            Self::Mwss => &Info {
+                name: "Middleweight spaceship",
+                kind: PatternKind::Spaceship,
+                rule: CONWAY,
+                period: Some(4),
+                offset: (-2, 0),
+                lifespan: None,
+                rle: "#N Middleweight spaceship\nx = 6, y = 5, rule = B3/S23\n3bo$bo3bo$o$o4bo$5o!",
+            },
+            Self::Hwss => &Info {
+                name: "Heavyweight spaceship",
+                kind: PatternKind::Spaceship,
+                rule: CONWAY,
+                period: Some(4),
+                offset: (-2, 0),
+                lifespan: None,
+                rle: "#N Heavyweight spaceship\nx = 7, y = 5, rule = B3/S23\n3b2o$bo4bo$o$o5bo$6o!",
+            },
+            Self::GosperGliderGun => &Info {
+                name: "Gosper glider gun",
+                kind: PatternKind::Gun,
+                rule: CONWAY,
+                period: Some(30),
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Gosper glider gun\nx = 36, y = 9, rule = B3/S23\n\
+                      24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+                      2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+            },
+            Self::RPentomino => &Info {
+                name: "R-pentomino",
+                kind: PatternKind::Methuselah,
+                rule: CONWAY,
+                period: None,
+                offset: (0, 0),
+                lifespan: Some((1103, 116)),
+                rle: "#N R-pentomino\nx = 3, y = 3, rule = B3/S23\nb2o$2o$bo!",
+            },
+            Self::Acorn => &Info {
+                name: "Acorn",
+                kind: PatternKind::Methuselah,
+                rule: CONWAY,
+                period: None,
+                offset: (0, 0),
+                lifespan: Some((5206, 633)),
+                rle: "#N Acorn\nx = 7, y = 3, rule = B3/S23\nbo$3bo$2o2b3o!",
+            },
+            Self::Diehard => &Info {
+                name: "Diehard",
+                kind: PatternKind::Methuselah,
+                rule: CONWAY,
+                period: None,
+                offset: (0, 0),
+                lifespan: Some((130, 0)),
+                rle: "#N Diehard\nx = 8, y = 3, rule = B3/S23\n6bo$2o$bo3b3o!",
+            },
+            Self::Rule30Seed => &Info {
+                name: "Rule 30 seed",
+                kind: PatternKind::Seed,
+                rule: PatternRule::Elementry(30),
+                period: None,
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Rule 30 seed\nx = 1, y = 1, rule = W30\no!",
+            },
+            Self::Rule90Seed => &Info {
+                name: "Rule 90 seed",
+                kind: PatternKind::Seed,
+                rule: PatternRule::Elementry(90),
+                period: None,
+                offset: (0, 0),
+                lifespan: None,
+                rle: "#N Rule 90 seed\nx = 1, y = 1, rule = W90\no!",
+            },
+            Self::Rule184Car => &Info {
+                name: "Rule 184 car",
+                kind: PatternKind::Spaceship,
+                rule: PatternRule::Elementry(184),
+                period: Some(1),
+                offset: (1, 0),
+                lifespan: None,
+                rle: "#N Rule 184 car\nx = 1, y = 1, rule = W184\no!",
+            },
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Elementry, Hashlife, Life};
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[test]
+    fn check_rle_matches_metadata() {
+        for pattern in Pattern::ALL {
+            let rle = Rle::parse(pattern.rle()).unwrap();
+            assert_eq!(rle.name.as_deref(), Some(pattern.name()));
+            assert!(!rle.grid.is_empty(), "{pattern} has no cells");
+
+            let rule = rle.rule.unwrap();
+            match pattern.rule() {
+                PatternRule::Life(life) => assert_eq!(LifeRule::parse(&rule), Some(life)),
+                PatternRule::Elementry(number) => assert_eq!(rule, alloc::format!("W{number}")),
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::exact("Glider", Some(Pattern::Glider))]
+    #[case::lowercase("pulsar", Some(Pattern::Pulsar))]
+    #[case::punctuation("r pentomino", Some(Pattern::RPentomino))]
+    #[case::abbreviation("LWSS", Some(Pattern::Lwss))]
+    #[case::long_name("Heavyweight Spaceship", Some(Pattern::Hwss))]
+    #[case::unknown("Garden of Eden", None)]
+    fn check_from_name(#[case] name: &str, #[case] expected: Option<Pattern>) {
+        assert_eq!(Pattern::from_name(name), expected);
+    }
+
+    #[test]
+    fn check_life_cycles_match_metadata() {
+        let periodic = Pattern::ALL.into_iter().filter(|pattern| {
+            matches!(
+                pattern.kind(),
+                PatternKind::StillLife | PatternKind::Oscillator | PatternKind::Spaceship
+            ) && matches!(pattern.rule(), PatternRule::Life(_))
+        });
+
+        for pattern in periodic {
+            let PatternRule::Life(rule) = pattern.rule() else {
+                unreachable!()
+            };
+            let grid = pattern.grid();
+            let margin = 8;
+            let cells = BitGrid::new(
+                grid.width() as usize + 2 * margin,
+                grid.height() as usize + 2 * margin,
+                1,
+            )
+            .with_boundary(Boundary::Dead);
+
+            let mut life = Life::new_with_cells_and_rule(rule, cells);
+            let at = IVec3::new(margin as Index, margin as Index, 0);
+            life.cells_mut().stamp(&grid, at, Transform::Identity);
+
+            let cycle = life.find_cycle(20, 20).unwrap();
+            assert_eq!(
+                (cycle.start, Some(cycle.period), cycle.offset),
+                (0, pattern.period(), pattern.offset()),
+                "{pattern}"
+            );
+            let kind = match () {
+                _ if cycle.is_still_life() => PatternKind::StillLife,
+                _ if cycle.is_oscillator() => PatternKind::Oscillator,
+                _ => PatternKind::Spaceship,
+            };
+            assert_eq!(kind, pattern.kind(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn check_gun_fires_every_period() {
+        let gun = Pattern::GosperGliderGun;
+        let period = gun.period().unwrap();
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &gun.grid());
+
+        life.advance(period);
+        let mut population = life.population();
+        for _ in 0..4 {
+            life.advance(period);
+            assert_eq!(life.population(), population + 5);
+            population = life.population();
+        }
+    }
+
+    #[rstest]
+    #[case::r_pentomino(Pattern::RPentomino)]
+    #[case::acorn(Pattern::Acorn)]
+    #[case::diehard(Pattern::Diehard)]
+    fn check_methuselah_lifespan(#[case] pattern: Pattern) {
+        let (lifespan, population) = pattern.lifespan().unwrap();
+        let mut life = Hashlife::from_bitgrid(LifeRule::CONWAY, &pattern.grid());
+
+        life.advance(lifespan - 1);
+        assert!(life.population() > 0);
+
+        // Settled patterns are made of still lifes, blinkers, and escaping gliders, so the population stops changing
+        life.advance(1);
+        for _ in 0..4 {
+            assert_eq!(life.population(), population, "{pattern}");
+            life.advance(2);
+        }
+    }
+
+    #[test]
+    fn check_elementry_patterns() {
+        let mut car: Elementry = Elementry::new(184, 16);
+        car.set(3, true);
+        let cycle = car.find_cycle(4, 4).unwrap();
+        assert_eq!(Some(cycle.period), Pattern::Rule184Car.period());
+        assert_eq!(cycle.offset, Pattern::Rule184Car.offset());
+
+        // Seeds keep growing while there's room, so they never repeat
+        for seed in [Pattern::Rule30Seed, Pattern::Rule90Seed] {
+            let PatternRule::Elementry(rule) = seed.rule() else {
+                unreachable!()
+            };
+            let mut elementry: Elementry = Elementry::new(rule, 128);
+            elementry.set(64, true);
+            assert_eq!(elementry.find_cycle(60, 64), None, "{seed}");
+        }
+    }
+}
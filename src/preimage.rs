@@ -0,0 +1,415 @@
+use crate::ElementryRule;
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Running [`Elementry`](crate::Elementry) backwards.
+///
+/// # Method
+/// These work on the de Bruijn graph of the rule: each node is a pair of neighboring cells `(a, b)`,
+/// and there is an edge from `(a, b)` to `(b, c)` labelled with the rule's next state for `(a, b, c)`.
+/// A predecessor of a row is a walk whose labels spell out the row, so for a ring it is a closed walk.
+/// Every question here is answered by walking 4 nodes per cell, which works for rows of any length.
+impl ElementryRule {
+    /// Every row that becomes `row` after one step, treating both as rings.
+    ///
+    /// Preimages are found one at a time, so there is no cost for ones that aren't used.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::ElementryRule;
+    /// // Rule 90 replaces each cell with the XOR of its neighbors, so a ring of 4 has four preimages of all dead
+    /// let row = [false; 4];
+    /// let rule_90 = ElementryRule::new(90);
+    /// assert_eq!(rule_90.preimages(&row).count(), 4);
+    /// assert_eq!(rule_90.count_preimages(&row), 4);
+    ///
+    /// // But nothing turns into a ring of 4 with a single alive cell
+    /// assert!(rule_90.is_garden_of_eden(&[true, false, false, false]));
+    /// ```
+    pub fn preimages(&self, row: &[bool]) -> Preimages {
+        Preimages {
+            rule: *self,
+            row: row.to_vec(),
+            start: 0,
+            reachable: Vec::new(),
+            cells: None,
+        }
+    }
+
+    /// Number of rows that become `row` after one step, treating both as rings.
+    ///
+    /// This doesn't list them, so it stays quick even when there are too many to list.
+    /// Counts too big for a `u128` saturate at `u128::MAX`.
+    pub fn count_preimages(&self, row: &[bool]) -> u128 {
+        if row.is_empty() {
+            return 1;
+        }
+
+        let mut total: u128 = 0;
+        for start in 0..4 {
+            // Number of walks from `start` that spell out the row so far, ending at each node
+            let mut walks = [0_u128; 4];
+            walks[start] = 1;
+
+            for &label in row {
+                let mut next = [0_u128; 4];
+                for (from, &count) in walks.iter().enumerate() {
+                    for to in self.edges(from, label) {
+                        next[to] = next[to].saturating_add(count);
+                    }
+                }
+                walks = next;
+            }
+
+            total = total.saturating_add(walks[start]);
+        }
+        total
+    }
+
+    /// Whether any row becomes `row` after one step, treating both as rings
+    pub fn has_preimage(&self, row: &[bool]) -> bool {
+        row.is_empty()
+            || (0..4).any(|start| reachable_from(*self, row, start)[0] & (1 << start) != 0)
+    }
+
+    /// Whether `row` is a Garden of Eden: a ring that no ring becomes after one step
+    pub fn is_garden_of_eden(&self, row: &[bool]) -> bool {
+        !self.has_preimage(row)
+    }
+
+    /// The shortest run of cells that can't appear anywhere in a row that has been stepped at least once.
+    ///
+    /// Any ring holding this run is a [Garden of Eden](Self::is_garden_of_eden). Among runs of the same length,
+    /// the one that comes first when read as a binary number from left to right is returned.
+    ///
+    /// Returns `None` if there is no such run, which is exactly when the rule is [surjective](Self::is_surjective).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::ElementryRule;
+    /// assert_eq!(ElementryRule::new(30).shortest_orphan(), None);
+    ///
+    /// // Rule 4 only keeps alive cells with dead neighbors, so no two alive cells can be next to each other
+    /// assert_eq!(ElementryRule::new(4).shortest_orphan(), Some(vec![true, true]));
+    /// ```
+    pub fn shortest_orphan(&self) -> Option<Vec<bool>> {
+        // Breadth-first search over sets of nodes where a walk spelling out the run could end.
+        // A run is an orphan when that set is empty.
+        const ALL: u8 = 0b1111;
+        let mut parents: [Option<(u8, bool)>; 16] = [None; 16];
+        let mut queue = VecDeque::from([ALL]);
+
+        while let Some(nodes) = queue.pop_front() {
+            for label in [false, true] {
+                let next = (0..4)
+                    .filter(|&from| nodes & (1 << from) != 0)
+                    .flat_map(|from| self.edges(from, label))
+                    .fold(0, |next, to| next | (1 << to));
+
+                if next == ALL || parents[next as usize].is_some() {
+                    continue;
+                }
+                parents[next as usize] = Some((nodes, label));
+
+                if next == 0 {
+                    let mut orphan = Vec::new();
+                    let mut at = next;
+                    while let Some((parent, label)) = parents[at as usize] {
+                        orphan.push(label);
+                        at = parent;
+                    }
+                    orphan.reverse();
+                    return Some(orphan);
+                }
+                queue.push_back(next);
+            }
+        }
+        None
+    }
+
+    /// Whether every row of an infinite line has a predecessor, so the rule has no Garden of Eden runs
+    pub fn is_surjective(&self) -> bool {
+        self.shortest_orphan().is_none()
+    }
+
+    /// The de Bruijn nodes reached from `from` along edges labelled `label`
+    fn edges(&self, from: usize, label: bool) -> impl Iterator<Item = usize> {
+        let (a, b) = (from & 2 != 0, from & 1 != 0);
+        let rule = *self;
+        [false, true]
+            .into_iter()
+            .filter(move |&c| rule.next_state(a, b, c) == label)
+            .map(move |c| node(b, c))
+    }
+}
+
+/// The de Bruijn node of two neighboring cells
+fn node(left: bool, right: bool) -> usize {
+    ((left as usize) << 1) | right as usize
+}
+
+/// For each position `i` of `row` (and one past the end), a mask of the nodes that can walk back to `start`
+/// at the end while spelling out the rest of `row`.
+fn reachable_from(rule: ElementryRule, row: &[bool], start: usize) -> Vec<u8> {
+    let mut reachable = vec![0; row.len() + 1];
+    reachable[row.len()] = 1 << start;
+
+    for i in (0..row.len()).rev() {
+        reachable[i] = (0..4)
+            .filter(|&from| {
+                rule.edges(from, row[i])
+                    .any(|to| reachable[i + 1] & (1 << to) != 0)
+            })
+            .fold(0, |mask, from| mask | (1 << from));
+    }
+    reachable
+}
+
+/// Iterator over the preimages of a ring. See [`ElementryRule::preimages`].
+///
+/// Each preimage is a ring as long as the row, in the same positions. Cell `i` of the row is
+/// the next state of cells `i - 1`, `i`, and `i + 1` of its preimage.
+#[derive(Clone, Debug)]
+pub struct Preimages {
+    rule: ElementryRule,
+
+    /// The row to find the preimages of
+    row: Vec<bool>,
+
+    /// The de Bruijn node `(last cell, first cell)` that preimages are being listed from
+    start: usize,
+
+    /// Nodes at each position that can still close the ring at `start`. See [`reachable_from`].
+    reachable: Vec<u8>,
+
+    /// The preimage returned last, if it was found from `start`
+    cells: Option<Vec<bool>>,
+}
+
+impl Preimages {
+    /// The cell before `i` in the preimage, wrapping around to the last cell for `i = 0`
+    fn before(&self, cells: &[bool], i: usize) -> bool {
+        match i {
+            0 => self.start & 2 != 0,
+            _ => cells[i - 1],
+        }
+    }
+
+    /// Whether `cells[i + 1] = next` keeps the ring closable at `start`
+    fn can_follow(&self, cells: &[bool], i: usize, next: bool) -> bool {
+        let (a, b) = (self.before(cells, i), cells[i]);
+        self.rule.next_state(a, b, next) == self.row[i]
+            && self.reachable[i + 1] & (1 << node(b, next)) != 0
+    }
+
+    /// Chooses every cell after `i` as dead where possible.
+    ///
+    /// Nodes in `reachable` always have a way forward, so this never gets stuck.
+    fn fill_from(&self, cells: &mut [bool], i: usize) {
+        for j in i..cells.len() - 1 {
+            cells[j + 1] = !self.can_follow(cells, j, false);
+        }
+    }
+
+    /// The first preimage from `start`, if there is one
+    fn first(&mut self) -> Option<Vec<bool>> {
+        self.reachable = reachable_from(self.rule, &self.row, self.start);
+        if self.reachable[0] & (1 << self.start) == 0 {
+            return None;
+        }
+
+        let mut cells = vec![false; self.row.len()];
+        cells[0] = self.start & 1 != 0;
+        self.fill_from(&mut cells, 0);
+        Some(cells)
+    }
+
+    /// The preimage after `cells` from `start`, if there is one.
+    ///
+    /// Preimages are counted up like binary numbers with cell `1` as the highest digit.
+    fn after(&self, mut cells: Vec<bool>) -> Option<Vec<bool>> {
+        // The last cell is always decided by `start`
+        let len = cells.len();
+        let i = (0..len.saturating_sub(2))
+            .rev()
+            .find(|&i| !cells[i + 1] && self.can_follow(&cells, i, true))?;
+
+        cells[i + 1] = true;
+        self.fill_from(&mut cells, i + 1);
+        Some(cells)
+    }
+}
+
+impl Iterator for Preimages {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row.is_empty() {
+            // An empty ring is its own only preimage
+            return (core::mem::replace(&mut self.start, 4) == 0).then(Vec::new);
+        }
+
+        while self.start < 4 {
+            let found = match self.cells.take() {
+                Some(cells) => self.after(cells),
+                None => self.first(),
+            };
+
+            match found {
+                Some(cells) => {
+                    self.cells = Some(cells.clone());
+                    return Some(cells);
+                }
+                None => self.start += 1,
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Elementry;
+
+    use alloc::collections::BTreeSet;
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    /// One step of `rule` on a ring
+    fn step(rule: ElementryRule, cells: &[bool]) -> Vec<bool> {
+        let len = cells.len();
+        (0..len)
+            .map(|i| rule.next_state(cells[(i + len - 1) % len], cells[i], cells[(i + 1) % len]))
+            .collect()
+    }
+
+    fn ring(bits: u32, len: usize) -> Vec<bool> {
+        (0..len).map(|i| bits >> i & 1 != 0).collect()
+    }
+
+    #[rstest]
+    #[case::rule_30(30)]
+    #[case::rule_90(90)]
+    #[case::rule_110(110)]
+    #[case::rule_184(184)]
+    #[case::rule_0(0)]
+    #[case::rule_204(204)]
+    fn check_preimages_match_brute_force(#[case] rule: u8) {
+        let rule = ElementryRule::new(rule);
+
+        for len in 1..=9 {
+            // Every ring of this length, grouped by what it steps to
+            let mut expected = vec![BTreeSet::new(); 1 << len];
+            for bits in 0..1_u32 << len {
+                let cells = ring(bits, len);
+                let next = step(rule, &cells)
+                    .iter()
+                    .rev()
+                    .fold(0, |bits, &cell| bits << 1 | cell as usize);
+                expected[next].insert(cells);
+            }
+
+            for (bits, expected) in expected.iter().enumerate() {
+                let row = ring(bits as u32, len);
+                let preimages: Vec<Vec<bool>> = rule.preimages(&row).collect();
+                let found: BTreeSet<Vec<bool>> = preimages.iter().cloned().collect();
+
+                assert_eq!(found.len(), preimages.len(), "Repeated preimage of {row:?}");
+                assert_eq!(&found, expected, "Preimages of {row:?}");
+                assert_eq!(rule.count_preimages(&row), expected.len() as u128);
+                assert_eq!(rule.is_garden_of_eden(&row), expected.is_empty());
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::rule_30(30)]
+    #[case::rule_110(110)]
+    fn check_preimages_of_long_rows(#[case] rule: u8) {
+        use rand::{rngs::SmallRng, SeedableRng};
+        let mut rng = SmallRng::seed_from_u64(0x5eed + rule as u64);
+
+        let mut sim: Elementry = Elementry::new(rule, 500);
+        sim.clear_random(&mut rng);
+        let before: Vec<bool> = sim.cells().collect();
+        sim.step();
+        let row: Vec<bool> = sim.cells().collect();
+
+        // Rule 30 has at most 4 preimages of any ring, but rule 110 can have far too many to list
+        let count = sim.count_preimages();
+        let preimages: Vec<Vec<bool>> = sim.preimages().take(1000).collect();
+        assert_eq!(preimages.len() as u128, count.min(1000));
+        for preimage in &preimages {
+            assert_eq!(step(sim.rule(), preimage), row);
+        }
+        if count <= 1000 {
+            assert!(preimages.contains(&before));
+        }
+    }
+
+    #[test]
+    fn check_count_saturates() {
+        // Rule 0 turns every ring dead
+        let rule_0 = ElementryRule::new(0);
+        assert_eq!(rule_0.count_preimages(&[false; 127]), 1 << 127);
+        assert_eq!(rule_0.count_preimages(&[false; 200]), u128::MAX);
+        assert_eq!(rule_0.count_preimages(&[true; 200]), 0);
+    }
+
+    #[test]
+    fn check_shortest_orphans() {
+        // Every run of `len` cells that something 2 cells longer steps into, as a binary number from left to right
+        let images = |rule: ElementryRule, len: usize| {
+            let mut seen = vec![false; 1 << len];
+            for bits in 0..1_u32 << (len + 2) {
+                let cells = ring(bits, len + 2);
+                let image = (0..len)
+                    .map(|i| rule.next_state(cells[i], cells[i + 1], cells[i + 2]))
+                    .fold(0, |bits, cell| bits << 1 | cell as usize);
+                seen[image] = true;
+            }
+            seen
+        };
+        let as_number = |run: &[bool]| run.iter().fold(0, |bits, &cell| bits << 1 | cell as usize);
+
+        for rule in (0..=255).map(ElementryRule::new) {
+            let orphan = rule.shortest_orphan();
+            assert_eq!(rule.is_surjective(), orphan.is_none(), "{rule}");
+
+            // Brute force: no shorter run is an orphan, and no run of the same length comes before it
+            let len = orphan.as_ref().map_or(usize::MAX, Vec::len);
+            for shorter in 1..len.min(9) {
+                assert!(images(rule, shorter).iter().all(|&seen| seen), "{rule}");
+            }
+            if let Some(orphan) = orphan {
+                let seen = images(rule, len);
+                assert_eq!(
+                    seen.iter().position(|&seen| !seen),
+                    Some(as_number(&orphan)),
+                    "{rule}"
+                );
+            }
+        }
+
+        // Permutive rules are always surjective
+        for rule in (0..=255).map(ElementryRule::new) {
+            if rule.is_left_permutive() || rule.is_right_permutive() {
+                assert!(rule.is_surjective(), "{rule}");
+            }
+        }
+    }
+
+    #[test]
+    fn check_orphan_rings_are_gardens_of_eden() {
+        let rule = ElementryRule::new(110);
+        let orphan = rule.shortest_orphan().unwrap();
+
+        let mut row = orphan.clone();
+        row.resize(40, false);
+        assert!(rule.is_garden_of_eden(&row));
+        assert_eq!(rule.preimages(&row).next(), None);
+    }
+}
@@ -0,0 +1,581 @@
+use crate::prelude::*;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// A pattern in the run length encoded (RLE) format used by Golly and the LifeWiki.
+///
+/// # Format
+/// ```txt
+/// #N Glider
+/// #C The smallest, most common, and first discovered spaceship.
+/// x = 3, y = 3, rule = B3/S23
+/// bo$2bo$3o!
+/// ```
+///
+/// - `#` lines come first. `#N` holds the name, `#C` (or `#c`) holds comments, and everything else is kept as-is in [`extra`](Self::extra).
+/// - The header gives the width (`x`) and height (`y`) of the pattern, and optionally its rule.
+/// - The body lists runs of cells: `b` (or `.`) is dead, `o` (or `A`) is alive, and `$` ends a row.
+///   Each may be prefixed with a run count, and `!` ends the pattern.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{BitGrid, Rle};
+/// let rle = Rle::parse("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+/// assert_eq!(rle.rule.as_deref(), Some("B3/S23"));
+/// assert_eq!(rle.grid.count_set(), 5);
+///
+/// // Writing it back out gives the same text
+/// assert_eq!(rle.to_string(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rle {
+    /// The pattern's name, from its `#N` line
+    pub name: Option<String>,
+
+    /// Comment lines from `#C` or `#c`, without the prefix
+    pub comments: Vec<String>,
+
+    /// Every other `#` line, such as `#O` (author) or `#R` (position), kept verbatim
+    pub extra: Vec<String>,
+
+    /// The rulestring from the header, if there was one
+    pub rule: Option<String>,
+
+    /// The cells of the pattern. Its dimensions match the `x` and `y` of the header.
+    pub grid: BitGrid,
+}
+
+impl Rle {
+    /// Longest line written by [`Display`](fmt::Display), as recommended by the format
+    const LINE_WIDTH: usize = 70;
+
+    /// Creates an `Rle` for `grid` with no name, comments, or rule.
+    pub fn new(grid: BitGrid) -> Self {
+        Self {
+            name: None,
+            comments: Vec::new(),
+            extra: Vec::new(),
+            rule: None,
+            grid,
+        }
+    }
+
+    /// Parses RLE text into its comments, header, and cells.
+    ///
+    /// Only two-state patterns are supported: any non-dead cell state is treated as **alive**.
+    /// Returns `None` if the header is missing or malformed, or if the cells don't fit in the header's dimensions.
+    pub fn parse(text: &str) -> Option<Self> {
+        let Sections {
+            name,
+            comments,
+            extra,
+            header,
+            body,
+        } = Sections::split(text)?;
+
+        let mut grid = BitGrid::new(header.width, header.height, 1);
+        parse_runs(&body, |x, y, state| {
+            if x >= header.width || y >= header.height {
+                return false;
+            }
+            if state != 0 {
+                grid.set(x as Index, y as Index, 0, true);
+            }
+            true
+        })?;
+
+        Some(Self {
+            name,
+            comments,
+            extra,
+            rule: header.rule,
+            grid,
+        })
+    }
+}
+
+/// RLE text split into its `#` lines, header, and undecoded body
+struct Sections {
+    name: Option<String>,
+    comments: Vec<String>,
+    extra: Vec<String>,
+    header: Header,
+    body: String,
+}
+
+impl Sections {
+    fn split(text: &str) -> Option<Self> {
+        let mut name = None;
+        let mut comments = Vec::new();
+        let mut extra = Vec::new();
+
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        let header = loop {
+            let line = lines.next()?;
+            if let Some(rest) = line.strip_prefix('#') {
+                let (tag, value) = rest.split_at(rest.chars().next().map_or(0, char::len_utf8));
+                let value = value.trim();
+                match tag {
+                    "N" => name = Some(value.into()),
+                    "C" | "c" => comments.push(value.into()),
+                    _ => extra.push(line.into()),
+                }
+            } else {
+                break line;
+            }
+        };
+
+        Some(Self {
+            name,
+            comments,
+            extra,
+            header: Header::parse(header)?,
+            body: lines.collect(),
+        })
+    }
+}
+
+/// Parses a multi-state RLE pattern into its header and cells, keeping every state instead of just dead and alive.
+///
+/// Comment lines are discarded. Returns `None` in the same cases as [`Rle::parse`].
+pub(crate) fn parse_states(text: &str) -> Option<(Header, PackedGrid<u8>)> {
+    let Sections { header, body, .. } = Sections::split(text)?;
+
+    let mut grid = PackedGrid::new(header.width, header.height, 1);
+    parse_runs(&body, |x, y, state| {
+        if x >= header.width || y >= header.height {
+            return false;
+        }
+        grid.set(x as Index, y as Index, 0, state);
+        true
+    })?;
+
+    Some((header, grid))
+}
+
+/// Writes the first layer of a multi-state grid in the RLE format, with states written as `.`, `A`, `B`, etc.
+pub(crate) fn write_states(grid: &PackedGrid<u8>, rule: Option<String>) -> String {
+    let header = Header {
+        width: grid.width() as usize,
+        height: grid.height() as usize,
+        rule,
+    };
+
+    let mut text = alloc::format!("{header}\n");
+    write_runs(&mut text, grid.width(), grid.height(), false, |x, y| {
+        grid.get(x, y, 0)
+    })
+    .expect("Writing to a String can't fail");
+    text
+}
+
+impl fmt::Display for Rle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "#N {name}")?;
+        }
+        for comment in &self.comments {
+            writeln!(f, "#C {comment}")?;
+        }
+        for line in &self.extra {
+            writeln!(f, "{line}")?;
+        }
+
+        let header = Header {
+            width: self.grid.width() as usize,
+            height: self.grid.height() as usize,
+            rule: self.rule.clone(),
+        };
+        writeln!(f, "{header}")?;
+
+        let grid = &self.grid;
+        write_runs(f, grid.width(), grid.height(), true, |x, y| {
+            grid.get(x, y, 0) as u8
+        })
+    }
+}
+
+/// The `x = .., y = .., rule = ..` line of an RLE file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub width: usize,
+    pub height: usize,
+    pub rule: Option<String>,
+}
+
+impl Header {
+    pub fn parse(line: &str) -> Option<Self> {
+        // The rule may contain commas itself (`B3/S23:T100,100`), so it always runs to the end of the line
+        let (dims, rule) = match line.find("rule") {
+            Some(i) => {
+                let (_, rule) = line[i..].split_once('=')?;
+                (&line[..i], Some(rule.trim().into()))
+            }
+            None => (line, None),
+        };
+
+        let mut width = None;
+        let mut height = None;
+        for field in dims.split(',').filter(|f| !f.trim().is_empty()) {
+            let (key, value) = field.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "x" => width = Some(value.parse().ok()?),
+                "y" => height = Some(value.parse().ok()?),
+                // Unknown keys are allowed and ignored
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            width: width?,
+            height: height?,
+            rule,
+        })
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x = {}, y = {}", self.width, self.height)?;
+        if let Some(rule) = &self.rule {
+            write!(f, ", rule = {rule}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the runs of an RLE body, calling `cell(x, y, state)` for every cell that isn't skipped by a `$`.
+///
+/// States are numbered like Golly does: `b` and `.` are `0`, `o` is `1`, `A` through `X` are `1` through `24`,
+/// and `pA` through `yO` continue on from `25`. Whitespace is ignored, and decoding stops at `!`.
+///
+/// Returns `None` on malformed input, or as soon as `cell` returns `false`.
+pub(crate) fn parse_runs(body: &str, mut cell: impl FnMut(usize, usize, u8) -> bool) -> Option<()> {
+    let (mut x, mut y) = (0, 0);
+    let mut count: Option<usize> = None;
+    let mut prefix: Option<u8> = None;
+
+    for c in body.chars() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if let Some(digit) = c.to_digit(10) {
+            if prefix.is_some() {
+                return None;
+            }
+            count = Some(
+                count
+                    .unwrap_or(0)
+                    .checked_mul(10)?
+                    .checked_add(digit as usize)?,
+            );
+            continue;
+        }
+
+        let run = count.take().unwrap_or(1);
+        let state = match (prefix.take(), c) {
+            (None, '!') => return Some(()),
+            (None, '$') => {
+                x = 0;
+                y += run;
+                continue;
+            }
+            (None, 'b' | '.') => 0,
+            (None, 'o') => 1,
+            (None, 'A'..='X') => c as u8 - b'A' + 1,
+            (None, 'p'..='y') => {
+                // Multi-character state; keep the run count for the state that follows
+                prefix = Some(c as u8 - b'p' + 1);
+                count = Some(run);
+                continue;
+            }
+            (Some(high), 'A'..='X') => {
+                let state = 24 * high as usize + (c as u8 - b'A') as usize + 1;
+                u8::try_from(state).ok()?
+            }
+            _ => return None,
+        };
+
+        for _ in 0..run {
+            if !cell(x, y, state) {
+                return None;
+            }
+            x += 1;
+        }
+    }
+
+    // A missing `!` is tolerated
+    Some(())
+}
+
+/// Encodes `width` by `height` cells as an RLE body ending with `!`, wrapping lines at 70 characters.
+///
+/// If `two_state` is set, states are written as `b` and `o`, otherwise as `.` and `A`, `B`, etc.
+/// Trailing dead cells in each row and trailing empty rows are left out.
+pub(crate) fn write_runs(
+    w: &mut impl Write,
+    width: Index,
+    height: Index,
+    two_state: bool,
+    state: impl Fn(Index, Index) -> u8,
+) -> fmt::Result {
+    let mut line_len = 0;
+    let mut emit = |w: &mut dyn Write, run: usize, state: Option<u8>| -> fmt::Result {
+        let mut token = String::new();
+        if run > 1 {
+            write!(token, "{run}")?;
+        }
+        match state {
+            None => token.push('$'),
+            Some(s) => push_state(&mut token, s, two_state),
+        }
+
+        if line_len + token.len() > Rle::LINE_WIDTH {
+            writeln!(w)?;
+            line_len = 0;
+        }
+        line_len += token.len();
+        w.write_str(&token)
+    };
+
+    // Empty rows since the last row written, or since the top of the pattern
+    let mut pending_rows = 0;
+    let mut written = false;
+    for y in 0..height {
+        // Only write up to the last non-dead cell of each row
+        let Some(last) = (0..width).rev().find(|&x| state(x, y) != 0) else {
+            pending_rows += 1;
+            continue;
+        };
+
+        // Ending the previous row takes one `$` on top of the empty rows
+        let rows = pending_rows + written as usize;
+        if rows > 0 {
+            emit(w, rows, None)?;
+        }
+        pending_rows = 0;
+        written = true;
+
+        let mut x = 0;
+        while x <= last {
+            let s = state(x, y);
+            let mut run = 1;
+            while x + run <= last && state(x + run, y) == s {
+                run += 1;
+            }
+            emit(w, run as usize, Some(s))?;
+            x += run;
+        }
+    }
+
+    writeln!(w, "!")
+}
+
+fn push_state(token: &mut String, state: u8, two_state: bool) {
+    match (state, two_state) {
+        (0, true) => token.push('b'),
+        (_, true) => token.push('o'),
+        (0, false) => token.push('.'),
+        (1..=24, false) => token.push((b'A' + state - 1) as char),
+        (_, false) => {
+            let high = (state - 1) / 24;
+            let low = (state - 1) % 24;
+            token.push((b'p' + high - 1) as char);
+            token.push((b'A' + low) as char);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    const GLIDER: &str = indoc!(
+        r#"
+        #N Glider
+        #C The smallest, most common, and first discovered spaceship.
+        x = 3, y = 3, rule = B3/S23
+        bo$2bo$3o!
+        "#
+    );
+
+    const GOSPER_GLIDER_GUN: &str = indoc!(
+        r#"
+        #N Gosper glider gun
+        #C This was the first gun discovered.
+        #C As its name suggests, it was discovered by Bill Gosper.
+        x = 36, y = 9, rule = B3/S23
+        24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+        obo$10bo5bo7bo$11bo3bo$12b2o!
+        "#
+    );
+
+    const PULSAR: &str = indoc!(
+        r#"
+        #N Pulsar
+        x = 13, y = 13, rule = B3/S23
+        2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o
+        4bobo4bo$o4bobo4bo2$2b3o3b3o!
+        "#
+    );
+
+    const R_PENTOMINO: &str = "x = 3, y = 3, rule = B3/S23\nb2o$2o$bo!\n";
+
+    const ACORN: &str = "#N Acorn\nx = 7, y = 3, rule = B3/S23\nbo$3bo$2o2b3o!\n";
+
+    const DIEHARD: &str = "#N Die hard\nx = 8, y = 3\n6bo$2o$bo3b3o!\n";
+
+    const LWSS: &str = "#N LWSS\n#O Unknown\nx = 5, y = 4, rule = 23/3\nbo2bo$o$o3bo$4o!\n";
+
+    const BLANK_FIRST_ROWS: &str = "x = 3, y = 5, rule = B3/S23\n2$bo2$2o!\n";
+
+    #[rstest]
+    #[case::glider(GLIDER)]
+    #[case::gosper_glider_gun(GOSPER_GLIDER_GUN)]
+    #[case::pulsar(PULSAR)]
+    #[case::r_pentomino(R_PENTOMINO)]
+    #[case::acorn(ACORN)]
+    #[case::diehard(DIEHARD)]
+    #[case::lwss(LWSS)]
+    #[case::blank_first_rows(BLANK_FIRST_ROWS)]
+    fn check_round_trip(#[case] text: &str) {
+        let rle = Rle::parse(text).unwrap();
+        let written = rle.to_string();
+
+        let reparsed = Rle::parse(&written).unwrap();
+        assert_eq!(reparsed, rle);
+
+        // Our own output is written exactly how we'd write it
+        assert_eq!(reparsed.to_string(), written);
+    }
+
+    #[rstest]
+    #[case::glider(GLIDER)]
+    #[case::gosper_glider_gun(GOSPER_GLIDER_GUN)]
+    #[case::pulsar(PULSAR)]
+    #[case::blank_first_rows(BLANK_FIRST_ROWS)]
+    fn check_write_matches_source(#[case] text: &str) {
+        assert_eq!(Rle::parse(text).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn check_parse_glider() {
+        let rle = Rle::parse(GLIDER).unwrap();
+
+        assert_eq!(rle.name.as_deref(), Some("Glider"));
+        assert_eq!(
+            rle.comments,
+            ["The smallest, most common, and first discovered spaceship."]
+        );
+        assert_eq!(rle.rule.as_deref(), Some("B3/S23"));
+
+        let expected = BitGrid::new_with_fn(3, 3, 1, |x, y, _z| {
+            matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2))
+        });
+        assert_eq!(rle.grid, expected);
+    }
+
+    #[test]
+    fn check_parse_gosper_glider_gun() {
+        let rle = Rle::parse(GOSPER_GLIDER_GUN).unwrap();
+        assert_eq!(rle.comments.len(), 2);
+        assert_eq!(rle.grid.dims(), IVec3::new(36, 9, 1));
+        assert_eq!(rle.grid.count_set(), 36);
+
+        // Spot check the two blocks on either end
+        for (x, y) in [
+            (0, 4),
+            (1, 4),
+            (0, 5),
+            (1, 5),
+            (34, 2),
+            (35, 2),
+            (34, 3),
+            (35, 3),
+        ] {
+            assert!(rle.grid.get(x, y, 0), "({x}, {y}) should be set");
+        }
+    }
+
+    #[test]
+    fn check_parse_extras() {
+        let rle = Rle::parse(LWSS).unwrap();
+        assert_eq!(rle.extra, ["#O Unknown"]);
+        assert_eq!(rle.rule.as_deref(), Some("23/3"));
+
+        let rle = Rle::parse(DIEHARD).unwrap();
+        assert_eq!(rle.rule, None);
+    }
+
+    #[rstest]
+    #[case::multi_digit("x = 12, y = 1\n12o!", 12)]
+    #[case::whitespace("x = 4, y = 2\n 2o $\n 2o\n!", 4)]
+    #[case::golly_states("x = 3, y = 1, rule = B3/S23\n.AA!", 2)]
+    #[case::missing_bang("x = 2, y = 1\n2o", 2)]
+    #[case::trailing_blank_rows("x = 2, y = 4\n2o3$!", 2)]
+    #[case::bounded_rule("x = 2, y = 1, rule = B3/S23:T100,100\n2o!", 2)]
+    fn check_parse_variants(#[case] text: &str, #[case] count: usize) {
+        assert_eq!(Rle::parse(text).unwrap().grid.count_set(), count);
+    }
+
+    #[rstest]
+    #[case::no_header("bo$2bo$3o!")]
+    #[case::bad_header("x = three, y = 3\nbo$2bo$3o!")]
+    #[case::too_wide("x = 2, y = 1\n3o!")]
+    #[case::too_tall("x = 1, y = 1\no$o!")]
+    #[case::unknown_token("x = 3, y = 1\nbzo!")]
+    fn check_parse_invalid(#[case] text: &str) {
+        assert_eq!(Rle::parse(text), None);
+    }
+
+    #[test]
+    fn check_multi_state_runs() {
+        let mut cells = Vec::new();
+        parse_runs("2.A$B3pA!", |x, y, s| {
+            cells.push((x, y, s));
+            true
+        })
+        .unwrap();
+        assert_eq!(
+            cells,
+            [
+                (0, 0, 0),
+                (1, 0, 0),
+                (2, 0, 1),
+                (0, 1, 2),
+                (1, 1, 25),
+                (2, 1, 25),
+                (3, 1, 25)
+            ]
+        );
+
+        let mut written = String::new();
+        write_runs(&mut written, 4, 2, false, |x, y| {
+            cells
+                .iter()
+                .find(|&&(cx, cy, _)| (cx, cy) == (x as usize, y as usize))
+                .map_or(0, |c| c.2)
+        })
+        .unwrap();
+        assert_eq!(written, "2.A$B3pA!\n");
+    }
+
+    #[test]
+    fn check_write_blank_first_row() {
+        let mut grid = BitGrid::new(3, 3, 1);
+        grid.set(1, 1, 0, true);
+        assert_eq!(grid.to_rle(), "x = 3, y = 3\n$bo!\n");
+
+        let mut states = PackedGrid::<u8>::new(3, 3, 1);
+        states.set(2, 1, 0, 3);
+        assert_eq!(write_states(&states, None), "x = 3, y = 3\n$2.C!\n");
+    }
+}
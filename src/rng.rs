@@ -0,0 +1,336 @@
+use crate::prelude::*;
+use crate::Elementry;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A pseudo-random number generator that reads bits from [Rule 30](https://en.wikipedia.org/wiki/Rule_30).
+///
+/// A ring of cells is stepped with rule 30 by an [`Elementry`], and each step gives one bit for each of the
+/// [`taps`](Self::taps): the cells that are read. By default the only tap is the center cell,
+/// which is Wolfram's classic generator.
+///
+/// This needs nothing but this crate, so it works without `std`. It is neither fast nor cryptographically secure.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Elementry, Rule30Rng};
+/// use rand::{Rng, SeedableRng};
+///
+/// let mut rng = Rule30Rng::seed_from_u64(30);
+/// let roll = rng.random_range(1..=6);
+/// assert!((1..=6).contains(&roll));
+///
+/// // Rule 30 can fill a simulation with random cells for another rule
+/// let mut sim: Elementry = Elementry::new(110, 64);
+/// sim.clear_random(&mut rng);
+/// ```
+#[derive(Clone)]
+pub struct Rule30Rng {
+    /// The ring of cells that bits are read from
+    sim: Elementry,
+
+    /// Positions of the cells read after every step
+    taps: Vec<Index>,
+
+    /// Bits read but not yet returned, lowest first
+    buffer: u64,
+
+    /// Number of valid bits in `buffer`
+    buffered: u32,
+}
+
+impl Rule30Rng {
+    /// Width of the ring used by [`from_seed`](rand::SeedableRng::from_seed), one cell for each bit of the seed
+    pub const DEFAULT_WIDTH: usize = 256;
+
+    /// Creates a generator on a ring of `width` cells, filled from `seed` and tapped at the center.
+    ///
+    /// The ring is stepped `width` times before any bits are read, so that every bit of the seed affects the output.
+    ///
+    /// # Panics
+    /// If `width` is `0`.
+    #[track_caller]
+    pub fn new(width: usize, seed: u64) -> Self {
+        assert!(width > 0, "Rule30Rng needs at least 1 cell");
+
+        let mut state = seed;
+        let mut words = vec![0; width.div_ceil(64)];
+        for word in &mut words {
+            *word = split_mix(&mut state);
+        }
+        Self::from_words(width, &words)
+    }
+
+    /// Reads bits from the cells of an existing simulation, from its current generation on.
+    ///
+    /// Nothing is changed about `sim`, so it can use any rule, boundary, or cells. It is tapped at the center.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{Elementry, Rule30Rng};
+    /// use rand::RngCore;
+    ///
+    /// // Rule 30's center column from a single alive cell: 1, 1, 0, 1, 1, 1, 0, 0, ...
+    /// let mut sim: Elementry = Elementry::new(30, 65);
+    /// sim.set(32, true);
+    /// let mut rng = Rule30Rng::from_elementry(sim);
+    /// assert_eq!(rng.next_u32() & 0xff, 0b0011_1011);
+    /// ```
+    pub fn from_elementry(sim: Elementry) -> Self {
+        let center = sim.width() / 2;
+        Self {
+            sim,
+            taps: vec![center],
+            buffer: 0,
+            buffered: 0,
+        }
+    }
+
+    /// Reads from the cells at `taps` instead, which gives as many bits per step as there are taps.
+    ///
+    /// Cells next to each other are strongly related, so taps should be spread out.
+    /// Bits that were read but not returned yet are thrown away.
+    ///
+    /// # Panics
+    /// If `taps` is empty, has more than 64 cells, or reads past the end of the ring.
+    #[track_caller]
+    pub fn with_taps(mut self, taps: &[usize]) -> Self {
+        assert!(
+            (1..=64).contains(&taps.len()),
+            "Rule30Rng needs 1 to 64 taps, but was given {}",
+            taps.len()
+        );
+        assert!(
+            taps.iter().all(|&x| x < self.width()),
+            "Taps must be inside the ring of {} cells, but were {taps:?}",
+            self.width()
+        );
+
+        self.taps = taps.iter().map(|&x| x as Index).collect();
+        self.buffer = 0;
+        self.buffered = 0;
+        self
+    }
+
+    /// Number of cells in the ring
+    pub fn width(&self) -> usize {
+        self.sim.width() as usize
+    }
+
+    /// Positions of the cells read after every step
+    pub fn taps(&self) -> impl Iterator<Item = usize> + '_ {
+        self.taps.iter().map(|&x| x as usize)
+    }
+
+    /// Borrow the simulation the bits are read from
+    pub fn elementry(&self) -> &Elementry {
+        &self.sim
+    }
+
+    /// Fills a ring with `words` as packed cells, then warms it up. See [`new`](Self::new).
+    fn from_words(width: usize, words: &[u64]) -> Self {
+        let mut sim: Elementry = Elementry::new(30, width);
+        for x in 0..width {
+            sim.set(x as Index, words[x / 64] >> (x % 64) & 1 != 0);
+        }
+
+        // An empty ring stays empty forever
+        if sim.cells().all(|is_alive| !is_alive) {
+            sim.set(width as Index / 2, true);
+        }
+
+        for _ in 0..width {
+            sim.step();
+        }
+        Self::from_elementry(sim)
+    }
+
+    /// Returns the next `count <= 64` bits, stepping the ring as needed
+    fn next_bits(&mut self, count: u32) -> u64 {
+        let mut bits = 0;
+        let mut filled = 0;
+
+        while filled < count {
+            if self.buffered == 0 {
+                for &x in &self.taps {
+                    self.buffer |= (self.sim.get(x) as u64) << self.buffered;
+                    self.buffered += 1;
+                }
+                self.sim.step();
+            }
+
+            let n = u32::min(count - filled, self.buffered);
+            let mask = u64::MAX.checked_shr(64 - n).unwrap_or(0);
+            bits |= (self.buffer & mask) << filled;
+            self.buffer = self.buffer.checked_shr(n).unwrap_or(0);
+            self.buffered -= n;
+            filled += n;
+        }
+        bits
+    }
+}
+
+impl rand::RngCore for Rule30Rng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_bits(32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits(64)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+impl rand::SeedableRng for Rule30Rng {
+    /// One bit for each of the [`DEFAULT_WIDTH`](Rule30Rng::DEFAULT_WIDTH) cells
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let words: Vec<u64> = seed
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        Self::from_words(Self::DEFAULT_WIDTH, &words)
+    }
+}
+
+impl fmt::Debug for Rule30Rng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule30Rng")
+            .field("width", &self.width())
+            .field("taps", &self.taps)
+            .field("generation", &self.sim.generation())
+            .finish()
+    }
+}
+
+/// One step of SplitMix64, used to spread a `u64` seed over any number of cells
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rand::{RngCore, SeedableRng};
+    use rstest::*;
+
+    /// Chi-square statistic of `bytes` against a uniform distribution over all 256 values
+    fn chi_square(bytes: &[u8]) -> f64 {
+        let mut counts = [0_u32; 256];
+        for &byte in bytes {
+            counts[byte as usize] += 1;
+        }
+
+        let expected = bytes.len() as f64 / 256.0;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[rstest]
+    #[case::center(Rule30Rng::seed_from_u64(1))]
+    #[case::narrow(Rule30Rng::new(101, 2))]
+    #[case::spread_taps(Rule30Rng::new(256, 3).with_taps(&[0, 64, 128, 192]))]
+    fn check_chi_square_on_bytes(#[case] mut rng: Rule30Rng) {
+        let mut bytes = vec![0; 256 * 64];
+        rng.fill_bytes(&mut bytes);
+
+        // With 255 degrees of freedom, uniform bytes stay under 330 all but about 0.1% of the time
+        let chi_square = chi_square(&bytes);
+        assert!(chi_square < 330.0, "Chi-square of {chi_square}");
+
+        // Half of all bits are set, give or take a few standard deviations
+        let ones: u32 = bytes.iter().map(|byte| byte.count_ones()).sum();
+        let bits = 8.0 * bytes.len() as f64;
+        assert!((ones as f64 - bits / 2.0).abs() < 4.0 * (bits / 4.0).sqrt());
+    }
+
+    #[test]
+    fn check_center_column() {
+        // OEIS A051023: the center column of rule 30 from a single alive cell
+        let expected = [
+            1, 1, 0, 1, 1, 1, 0, 0, 1, 1, 0, 0, 0, 1, 0, 1, 1, 0, 0, 1, 0, 0, 1, 1, 1,
+        ];
+
+        let mut sim: Elementry = Elementry::new(30, 101);
+        sim.set(50, true);
+        let mut rng = Rule30Rng::from_elementry(sim);
+
+        let bits = rng.next_u64();
+        let column: Vec<u64> = (0..expected.len()).map(|i| bits >> i & 1).collect();
+        assert_eq!(column, expected);
+        assert_eq!(rng.elementry().generation(), 64);
+    }
+
+    #[test]
+    fn check_seeds() {
+        let next = |mut rng: Rule30Rng| [rng.next_u64(), rng.next_u64()];
+
+        assert_eq!(next(Rule30Rng::new(64, 5)), next(Rule30Rng::new(64, 5)));
+        assert_ne!(next(Rule30Rng::new(64, 5)), next(Rule30Rng::new(64, 6)));
+        assert_eq!(
+            next(Rule30Rng::from_seed([7; 32])),
+            next(Rule30Rng::from_seed([7; 32]))
+        );
+
+        // An empty seed still gives bits
+        let mut rng = Rule30Rng::from_seed([0; 32]);
+        assert_eq!(rng.width(), Rule30Rng::DEFAULT_WIDTH);
+        assert_ne!(next(rng.clone()), [0, 0]);
+        assert_ne!(rng.next_u32(), rng.next_u32());
+    }
+
+    #[test]
+    fn check_taps_give_bits_per_step() {
+        let taps = [3, 20, 40, 57];
+        let mut rng = Rule30Rng::new(64, 9).with_taps(&taps);
+        let sim = rng.elementry().clone();
+        assert_eq!(rng.taps().collect::<Vec<_>>(), taps);
+
+        // Each step gives one bit from each tap, in order
+        let bits = rng.next_u32();
+        let mut sim = sim;
+        let mut expected = 0;
+        for step in 0..8 {
+            for (i, &x) in taps.iter().enumerate() {
+                expected |= (sim.get(x as Index) as u32) << (4 * step + i);
+            }
+            sim.step();
+        }
+        assert_eq!(bits, expected);
+        assert_eq!(rng.elementry().generation(), sim.generation());
+    }
+
+    #[test]
+    fn check_split_reads_match() {
+        let mut whole = Rule30Rng::new(80, 11);
+        let mut parts = whole.clone();
+
+        let expected = whole.next_u64();
+        let low = parts.next_u32() as u64;
+        let high = parts.next_u32() as u64;
+        assert_eq!(low | high << 32, expected);
+
+        let mut bytes = [0; 11];
+        whole.fill_bytes(&mut bytes);
+        let mut words = [0; 16];
+        parts.fill_bytes(&mut words);
+        assert_eq!(bytes, words[..11]);
+    }
+}
@@ -0,0 +1,1000 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A Life-like rule for [`Life`](crate::Life), written in B/S notation.
+///
+/// A dead cell is **born** when its number of alive neighbors is in the `B` set,
+/// and an alive cell **survives** when its number of alive neighbors is in the `S` set.
+/// Every other cell is dead in the next generation.
+///
+/// # Notation
+/// Both common spellings are accepted by [`parse`](Self::parse):
+/// - `B3/S23`: Birth then survival, each prefixed by its letter. The letters may be lowercase, or in either order.
+/// - `23/3`: Survival then birth, without letters. This is the older "S/B" notation.
+///
+/// Either set may be empty, as in `B2/S` (Seeds). Rules are always printed as `B.../S...`.
+///
+/// # B0
+/// Rules with `0` in the birth set (e.g. `B0/S8`) turn every dead cell with no alive neighbors on in a single step.
+/// On a finite grid like a wrapping [`BitGrid`](crate::BitGrid) this is simulated directly:
+/// an empty torus becomes full after one step, and whatever the survival set says happens next.
+///
+/// # Example
+/// ```rust
+/// # use simulations::LifeRule;
+/// let highlife = LifeRule::parse("B36/S23").unwrap();
+/// assert_eq!(highlife, LifeRule::HIGHLIFE);
+///
+/// // S/B notation is printed back as B/S
+/// let conway = LifeRule::parse("23/3").unwrap();
+/// assert_eq!(conway.to_string(), "B3/S23");
+///
+/// assert!(conway.is_born(3));
+/// assert!(conway.survives(2));
+/// assert!(!conway.survives(4));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    /// Bit `n` is set when a dead cell with `n` alive neighbors is born
+    birth: u16,
+
+    /// Bit `n` is set when an alive cell with `n` alive neighbors survives
+    survival: u16,
+}
+
+impl LifeRule {
+    /// Conway's Game of Life: `B3/S23`
+    pub const CONWAY: Self = Self::from_masks(0b0_0000_1000, 0b0_0000_1100);
+
+    /// HighLife, which has a replicator: `B36/S23`
+    pub const HIGHLIFE: Self = Self::from_masks(0b0_0100_1000, 0b0_0000_1100);
+
+    /// Day & Night, which is symmetric under swapping alive and dead cells: `B3678/S34678`
+    pub const DAY_AND_NIGHT: Self = Self::from_masks(0b1_1100_1000, 0b1_1101_1000);
+
+    /// Seeds, where no cell survives: `B2/S`
+    pub const SEEDS: Self = Self::from_masks(0b0_0000_0100, 0b0_0000_0000);
+
+    /// Maze, which grows maze-like corridors: `B3/S12345`
+    pub const MAZE: Self = Self::from_masks(0b0_0000_1000, 0b0_0011_1110);
+
+    /// Life without Death, where every cell survives: `B3/S012345678`
+    pub const LIFE_WITHOUT_DEATH: Self = Self::from_masks(0b0_0000_1000, 0b1_1111_1111);
+
+    /// Creates a rule from bitmasks of neighbor counts, where bit `n` represents `n` alive neighbors.
+    ///
+    /// Bits above `8` are ignored.
+    pub const fn from_masks(birth: u16, survival: u16) -> Self {
+        Self {
+            birth: birth & 0x1ff,
+            survival: survival & 0x1ff,
+        }
+    }
+
+    /// Parses a rulestring like `B36/S23` or `23/36`. See the type-level docs for the accepted notation.
+    ///
+    /// Returns `None` if `text` isn't a valid Life-like rulestring.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (first, second) = text.split_once('/')?;
+        let (first, second) = (first.trim(), second.trim());
+
+        let lettered = |part: &str| part.starts_with(['B', 'b', 'S', 's']);
+        if lettered(first) && lettered(second) {
+            let mut birth = None;
+            let mut survival = None;
+            for part in [first, second] {
+                let (letter, digits) = part.split_at(1);
+                let slot = if letter.eq_ignore_ascii_case("b") {
+                    &mut birth
+                } else {
+                    &mut survival
+                };
+
+                // Each letter may only appear once
+                if slot.is_some() {
+                    return None;
+                }
+                *slot = Some(parse_counts(digits)?);
+            }
+
+            Some(Self::from_masks(birth?, survival?))
+        } else if !lettered(first) && !lettered(second) {
+            // S/B notation
+            Some(Self::from_masks(
+                parse_counts(second)?,
+                parse_counts(first)?,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Bitmask of neighbor counts where dead cells are born
+    pub fn birth_mask(&self) -> u16 {
+        self.birth
+    }
+
+    /// Bitmask of neighbor counts where alive cells survive
+    pub fn survival_mask(&self) -> u16 {
+        self.survival
+    }
+
+    /// Whether a dead cell with `neighbors` alive neighbors is born
+    pub fn is_born(&self, neighbors: u8) -> bool {
+        neighbors <= 8 && (self.birth & (1 << neighbors)) != 0
+    }
+
+    /// Whether an alive cell with `neighbors` alive neighbors survives
+    pub fn survives(&self, neighbors: u8) -> bool {
+        neighbors <= 8 && (self.survival & (1 << neighbors)) != 0
+    }
+
+    /// Whether a cell is alive in the next generation, given its current state and its number of alive neighbors
+    pub fn next_state(&self, is_alive: bool, neighbors: u8) -> bool {
+        if is_alive {
+            self.survives(neighbors)
+        } else {
+            self.is_born(neighbors)
+        }
+    }
+
+    /// Whether empty space turns alive under this rule. See the type-level docs.
+    pub fn has_b0(&self) -> bool {
+        self.is_born(0)
+    }
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        write_counts(f, self.birth)?;
+        write!(f, "/S")?;
+        write_counts(f, self.survival)
+    }
+}
+
+impl fmt::Debug for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LifeRule({self})")
+    }
+}
+
+/// A rule from the Generations family, written in B/S/C notation.
+///
+/// Generations rules extend [`LifeRule`] with **dying** states. Cells that would die under the Life-like rule
+/// instead count down through `states - 2` dying states before they become dead, and dying cells don't count
+/// as alive neighbors or get born again until they have fully decayed.
+///
+/// Cell states are numbered like Golly does:
+/// - `0` is dead
+/// - `1` is alive
+/// - `2` through `states - 1` are dying, with higher states being older
+///
+/// # Notation
+/// Both common spellings are accepted by [`parse`](Self::parse):
+/// - `B2/S/C3`: Birth, survival, and state count, each prefixed by its letter. The letters may be lowercase or in any order,
+///   and `G` may be used instead of `C`.
+/// - `/2/3`: Survival, birth, then the state count, without letters.
+///
+/// Rules are always printed as `B.../S.../C...`. A rule with 2 states is the same as its Life-like rule.
+///
+/// # Example
+/// ```rust
+/// # use simulations::GenerationsRule;
+/// let brain = GenerationsRule::parse("/2/3").unwrap();
+/// assert_eq!(brain, GenerationsRule::BRIANS_BRAIN);
+/// assert_eq!(brain.to_string(), "B2/S/C3");
+///
+/// // Alive cells that don't survive start dying, and dying cells keep decaying
+/// assert_eq!(brain.next_state(1, 3), 2);
+/// assert_eq!(brain.next_state(2, 2), 0);
+/// assert_eq!(brain.next_state(0, 2), 1);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+    /// Which neighbor counts cause births and survival
+    life: LifeRule,
+
+    /// Total number of states, including dead and alive
+    states: u8,
+}
+
+impl GenerationsRule {
+    /// Brian's Brain, where every alive cell dies after one step: `B2/S/C3`
+    pub const BRIANS_BRAIN: Self = Self::new(LifeRule::SEEDS, 3);
+
+    /// Star Wars: `B2/S345/C4`
+    pub const STAR_WARS: Self = Self::new(LifeRule::from_masks(0b0_0000_0100, 0b0_0011_1000), 4);
+
+    /// Creates a rule that uses `life` to decide births and survival, with `states` states in total.
+    ///
+    /// # Panics
+    /// If `states` is less than 2.
+    #[track_caller]
+    pub const fn new(life: LifeRule, states: u8) -> Self {
+        assert!(states >= 2, "Generations rules need at least 2 states");
+        Self { life, states }
+    }
+
+    /// Parses a rulestring like `B2/S/C3` or `/2/3`. See the type-level docs for the accepted notation.
+    ///
+    /// Returns `None` if `text` isn't a valid Generations rulestring.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split('/').map(str::trim);
+        let (first, second, third) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let states_of = |digits: &str| digits.parse::<u8>().ok().filter(|&n| n >= 2);
+
+        if third.starts_with(['B', 'b', 'S', 's', 'C', 'c', 'G', 'g']) {
+            // Pull the state count out, and let LifeRule handle the remaining B and S parts
+            let mut states = None;
+            let mut life = Vec::with_capacity(2);
+            for part in [first, second, third] {
+                match part.strip_prefix(['C', 'c', 'G', 'g']) {
+                    Some(_) if states.is_some() => return None,
+                    Some(digits) => states = Some(states_of(digits)?),
+                    None => life.push(part),
+                }
+            }
+
+            // Both remaining parts must be lettered too
+            let [b, s] = life[..] else {
+                return None;
+            };
+            if !(b.starts_with(['B', 'b', 'S', 's']) && s.starts_with(['B', 'b', 'S', 's'])) {
+                return None;
+            }
+            let life = LifeRule::parse(&alloc::format!("{b}/{s}"))?;
+
+            Some(Self::new(life, states?))
+        } else {
+            // S/B/C notation
+            let life = LifeRule::from_masks(parse_counts(second)?, parse_counts(first)?);
+            Some(Self::new(life, states_of(third)?))
+        }
+    }
+
+    /// The Life-like rule that decides births and survival
+    pub fn life(&self) -> LifeRule {
+        self.life
+    }
+
+    /// Total number of states, including dead and alive
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The state of a cell in the next generation, given its current state and its number of **alive** neighbors
+    pub fn next_state(&self, state: u8, neighbors: u8) -> u8 {
+        match state {
+            0 => self.life.is_born(neighbors) as u8,
+            1 if self.life.survives(neighbors) => 1,
+            // Alive cells start dying and dying cells decay, until they run out of states
+            _ => match state.checked_add(1) {
+                Some(next) if next < self.states => next,
+                _ => 0,
+            },
+        }
+    }
+}
+
+impl Default for GenerationsRule {
+    fn default() -> Self {
+        Self::BRIANS_BRAIN
+    }
+}
+
+impl From<LifeRule> for GenerationsRule {
+    fn from(life: LifeRule) -> Self {
+        Self::new(life, 2)
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/C{}", self.life, self.states)
+    }
+}
+
+impl fmt::Debug for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GenerationsRule({self})")
+    }
+}
+
+/// One of the 256 elementary rules for [`Elementry`](crate::Elementry), numbered like Wolfram does.
+///
+/// Bit `n` of the number is the next state of a cell whose neighborhood reads as `n`,
+/// with the left neighbor as the highest bit and the right neighbor as the lowest.
+///
+/// # Equivalence
+/// Mirroring a rule left to right, or swapping alive and dead cells, gives a rule that behaves the same way.
+/// Together they make up to 4 equivalent rules, and the lowest numbered of them [represents](Self::representative)
+/// them all. There are 88 such classes.
+///
+/// # Example
+/// ```rust
+/// # use simulations::ElementryRule;
+/// let rule_30 = ElementryRule::new(30);
+/// assert_eq!(rule_30.mirror().number(), 86);
+/// assert_eq!(rule_30.complement().number(), 135);
+/// assert_eq!(rule_30.mirror_complement().number(), 149);
+/// assert_eq!(ElementryRule::new(149).representative(), rule_30);
+///
+/// assert!(rule_30.is_left_permutive());
+/// assert!(ElementryRule::new(90).is_additive());
+/// assert!(ElementryRule::new(184).is_number_conserving());
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElementryRule(u8);
+
+impl ElementryRule {
+    pub const fn new(number: u8) -> Self {
+        Self(number)
+    }
+
+    /// Parses a rule number, optionally prefixed with `Rule` or `W` like `Rule 30` or `W30`.
+    ///
+    /// Returns `None` if `text` isn't a number from `0` to `255`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let number = match text.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("rule") => &text[4..],
+            _ => text.strip_prefix(['W', 'w']).unwrap_or(text),
+        };
+        number.trim().parse().ok().map(Self)
+    }
+
+    /// The rule's number, from `0` to `255`
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    /// The next state of a cell, given it and its neighbors
+    pub fn next_state(&self, left: bool, center: bool, right: bool) -> bool {
+        let n = ((left as u8) << 2) | ((center as u8) << 1) | (right as u8);
+        (self.0 >> n) & 1 != 0
+    }
+
+    /// The rule that acts the same with left and right swapped
+    pub fn mirror(&self) -> Self {
+        self.remap(|l, c, r| (r, c, l), false)
+    }
+
+    /// The rule that acts the same with alive and dead swapped
+    pub fn complement(&self) -> Self {
+        self.remap(|l, c, r| (!l, !c, !r), true)
+    }
+
+    /// The rule that acts the same with left and right swapped, and alive and dead swapped
+    pub fn mirror_complement(&self) -> Self {
+        self.mirror().complement()
+    }
+
+    /// This rule and every rule equivalent to it: itself, its mirror, its complement, then its mirror-complement.
+    ///
+    /// Symmetric rules appear more than once.
+    pub fn equivalents(&self) -> [Self; 4] {
+        [
+            *self,
+            self.mirror(),
+            self.complement(),
+            self.mirror_complement(),
+        ]
+    }
+
+    /// The lowest numbered rule equivalent to this one, which stands for its whole class
+    pub fn representative(&self) -> Self {
+        self.equivalents().into_iter().min().unwrap_or(*self)
+    }
+
+    /// Whether this rule is the [`representative`](Self::representative) of its class
+    pub fn is_representative(&self) -> bool {
+        self.representative() == *self
+    }
+
+    /// The representatives of all 88 classes of equivalent rules, from lowest to highest
+    pub fn classes() -> impl Iterator<Item = Self> {
+        (0..=255).map(Self).filter(Self::is_representative)
+    }
+
+    /// Whether the next state is the XOR of some of `left`, `center`, and `right`.
+    ///
+    /// The 8 additive rules are `0`, `60`, `90`, `102`, `150`, `170`, `204`, and `240`.
+    /// Their complements also XOR their neighbors but then flip the result, so they don't count.
+    pub fn is_additive(&self) -> bool {
+        self.additive_terms().is_some()
+    }
+
+    /// For an [additive](Self::is_additive) rule, which of `[left, center, right]` are XORed together
+    pub fn additive_terms(&self) -> Option<[bool; 3]> {
+        let terms = [
+            self.next_state(true, false, false),
+            self.next_state(false, true, false),
+            self.next_state(false, false, true),
+        ];
+        let xor = |l: bool, c: bool, r: bool| (terms[0] & l) ^ (terms[1] & c) ^ (terms[2] & r);
+
+        (0..8)
+            .map(|n| (n & 4 != 0, n & 2 != 0, n & 1 != 0))
+            .all(|(l, c, r)| self.next_state(l, c, r) == xor(l, c, r))
+            .then_some(terms)
+    }
+
+    /// Whether every step keeps the number of alive cells on a ring the same, like the traffic rule `184`.
+    ///
+    /// There are 5 such rules: `170`, `184`, `204`, `226`, and `240`.
+    pub fn is_number_conserving(&self) -> bool {
+        // Boccara and Fukś: f(x, y, z) = x + f(0, y, z) - f(0, x, y) + f(0, 0, y) - f(0, 0, x)
+        let f = |l: bool, c: bool, r: bool| self.next_state(l, c, r) as i8;
+        (0..8)
+            .map(|n| (n & 4 != 0, n & 2 != 0, n & 1 != 0))
+            .all(|(x, y, z)| {
+                f(x, y, z)
+                    == x as i8 + f(false, y, z) - f(false, x, y) + f(false, false, y)
+                        - f(false, false, x)
+            })
+    }
+
+    /// Whether flipping the left neighbor always flips the next state, whatever the other two cells are
+    pub fn is_left_permutive(&self) -> bool {
+        (0..4).all(|n| {
+            let (c, r) = (n & 2 != 0, n & 1 != 0);
+            self.next_state(false, c, r) != self.next_state(true, c, r)
+        })
+    }
+
+    /// Whether flipping the right neighbor always flips the next state, whatever the other two cells are
+    pub fn is_right_permutive(&self) -> bool {
+        self.mirror().is_left_permutive()
+    }
+
+    /// Builds the rule that gives `self`'s next state for the neighborhood `map(l, c, r)`, flipped if `invert`
+    fn remap(&self, map: impl Fn(bool, bool, bool) -> (bool, bool, bool), invert: bool) -> Self {
+        let number = (0..8)
+            .filter(|n| {
+                let (l, c, r) = map(n & 4 != 0, n & 2 != 0, n & 1 != 0);
+                self.next_state(l, c, r) != invert
+            })
+            .fold(0, |number, n| number | (1 << n));
+        Self(number)
+    }
+}
+
+impl From<u8> for ElementryRule {
+    fn from(number: u8) -> Self {
+        Self(number)
+    }
+}
+
+impl From<ElementryRule> for u8 {
+    fn from(rule: ElementryRule) -> Self {
+        rule.0
+    }
+}
+
+impl fmt::Display for ElementryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rule {}", self.0)
+    }
+}
+
+impl fmt::Debug for ElementryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ElementryRule({})", self.0)
+    }
+}
+
+/// How the code of an [`AutomatonRule`] is read, which decides what each of its digits means
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CodeKind {
+    /// One digit for every arrangement of the neighborhood, like Wolfram's elementary rules
+    General,
+
+    /// One digit for every possible sum of the neighborhood, center included
+    Totalistic,
+
+    /// One digit for every pair of center state and sum of the other cells
+    OuterTotalistic,
+}
+
+/// A rule for an [`Automaton`](crate::Automaton): a 1D cellular automaton with `colors` states,
+/// where each cell looks `radius` cells to either side.
+///
+/// # Codes
+/// Rules are written as a number in base `colors`, following Wolfram's numbering. Digit `n` (the `n`th lowest)
+/// is the next state of the cell when its neighborhood reads as `n`:
+/// - [`General`](CodeKind::General): the states of the neighborhood, from left to right, as the digits of a number in base `colors`.
+///   Radius 1 with 2 colors gives the 256 elementary rules.
+/// - [`Totalistic`](CodeKind::Totalistic): the sum of the neighborhood's states.
+/// - [`OuterTotalistic`](CodeKind::OuterTotalistic): `colors * outer + center`, where `outer` is the sum of every state but the center.
+///
+/// General codes grow quickly with the radius and colors, so codes are given and returned as decimal strings of any length.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{AutomatonRule, CodeKind};
+/// // Rule 1635 with 3 colors: 1635 is 2020120 in base 3
+/// let rule = AutomatonRule::totalistic("1635", 3, 1).unwrap();
+/// assert_eq!(rule.next_state(&[0, 0, 1]), 2);
+/// assert_eq!(rule.next_state(&[1, 2, 1]), 2);
+///
+/// // Every totalistic rule is also a general rule, with a much longer code
+/// let general = rule.to_general();
+/// assert_eq!(general.kind(), CodeKind::General);
+/// assert_eq!(general.code(), "5718810141366");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AutomatonRule {
+    kind: CodeKind,
+    colors: u8,
+    radius: u8,
+
+    /// The digits of the code, lowest first
+    table: Vec<u8>,
+}
+
+impl AutomatonRule {
+    /// Largest number of digits a code can have
+    const MAX_TABLE_LEN: usize = 1 << 16;
+
+    /// Creates a rule from a decimal `code` read as `kind`.
+    ///
+    /// Returns `None` if `code` isn't a decimal number, if it is too big for `kind`, `colors`, and `radius`,
+    /// if `colors` is less than 2, or if the code would need more than 65536 digits.
+    pub fn new(kind: CodeKind, code: &str, colors: u8, radius: u8) -> Option<Self> {
+        let len = Self::table_len(kind, colors, radius)?;
+        let table = decimal_to_digits(code, colors, len)?;
+        Some(Self {
+            kind,
+            colors,
+            radius,
+            table,
+        })
+    }
+
+    /// Creates a rule from a general Wolfram code. See [`new`](Self::new).
+    pub fn general(code: &str, colors: u8, radius: u8) -> Option<Self> {
+        Self::new(CodeKind::General, code, colors, radius)
+    }
+
+    /// Creates a rule from a totalistic code. See [`new`](Self::new).
+    pub fn totalistic(code: &str, colors: u8, radius: u8) -> Option<Self> {
+        Self::new(CodeKind::Totalistic, code, colors, radius)
+    }
+
+    /// Creates a rule from an outer totalistic code. See [`new`](Self::new).
+    pub fn outer_totalistic(code: &str, colors: u8, radius: u8) -> Option<Self> {
+        Self::new(CodeKind::OuterTotalistic, code, colors, radius)
+    }
+
+    /// The elementary rule `rule`, as used by [`Elementry`](crate::Elementry)
+    pub fn elementry(rule: u8) -> Self {
+        let table = (0..8).map(|n| (rule >> n) & 1).collect();
+        Self {
+            kind: CodeKind::General,
+            colors: 2,
+            radius: 1,
+            table,
+        }
+    }
+
+    /// Number of digits in a code, if the rule is possible
+    fn table_len(kind: CodeKind, colors: u8, radius: u8) -> Option<usize> {
+        if colors < 2 {
+            return None;
+        }
+        let cells = 2 * radius as u32 + 1;
+        let max_state = colors as usize - 1;
+        let len = match kind {
+            CodeKind::General => (colors as usize).checked_pow(cells)?,
+            CodeKind::Totalistic => cells as usize * max_state + 1,
+            CodeKind::OuterTotalistic => colors as usize * ((cells as usize - 1) * max_state + 1),
+        };
+        (len <= Self::MAX_TABLE_LEN).then_some(len)
+    }
+
+    pub fn kind(&self) -> CodeKind {
+        self.kind
+    }
+
+    /// Number of states each cell can be in
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    /// Number of cells on either side of a cell that decide its next state
+    pub fn radius(&self) -> u8 {
+        self.radius
+    }
+
+    /// Number of cells in a neighborhood, including the center
+    pub fn width(&self) -> usize {
+        2 * self.radius as usize + 1
+    }
+
+    /// The code of this rule, in decimal
+    pub fn code(&self) -> String {
+        digits_to_decimal(&self.table, self.colors)
+    }
+
+    /// The same rule, written as a [`General`](CodeKind::General) code
+    pub fn to_general(&self) -> Self {
+        let len = Self::table_len(CodeKind::General, self.colors, self.radius)
+            .expect("Rules are never bigger than their general form");
+        let width = self.width();
+        let mut neighborhood = alloc::vec![0; width];
+
+        let table = (0..len)
+            .map(|mut n| {
+                for cell in neighborhood.iter_mut().rev() {
+                    *cell = (n % self.colors as usize) as u8;
+                    n /= self.colors as usize;
+                }
+                self.next_state(&neighborhood)
+            })
+            .collect();
+
+        Self {
+            kind: CodeKind::General,
+            colors: self.colors,
+            radius: self.radius,
+            table,
+        }
+    }
+
+    /// The next state of the center cell of `neighborhood`, which holds the states from left to right.
+    ///
+    /// # Panics
+    /// If `neighborhood` isn't [`width()`](Self::width) cells long, or holds a state that isn't one of the [`colors()`](Self::colors).
+    #[track_caller]
+    pub fn next_state(&self, neighborhood: &[u8]) -> u8 {
+        assert_eq!(
+            neighborhood.len(),
+            self.width(),
+            "A radius {} rule needs {} cells",
+            self.radius,
+            self.width()
+        );
+        assert!(
+            neighborhood.iter().all(|&state| state < self.colors),
+            "A {} color rule has no state in {neighborhood:?}",
+            self.colors
+        );
+
+        let k = self.colors as usize;
+        let sum = || {
+            neighborhood
+                .iter()
+                .map(|&state| state as usize)
+                .sum::<usize>()
+        };
+        let n = match self.kind {
+            CodeKind::General => neighborhood
+                .iter()
+                .fold(0, |n, &state| n * k + state as usize),
+            CodeKind::Totalistic => sum(),
+            CodeKind::OuterTotalistic => {
+                let center = neighborhood[self.radius as usize] as usize;
+                k * (sum() - center) + center
+            }
+        };
+        self.table[n]
+    }
+}
+
+impl From<u8> for AutomatonRule {
+    fn from(rule: u8) -> Self {
+        Self::elementry(rule)
+    }
+}
+
+impl From<ElementryRule> for AutomatonRule {
+    fn from(rule: ElementryRule) -> Self {
+        Self::elementry(rule.number())
+    }
+}
+
+impl fmt::Debug for AutomatonRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutomatonRule")
+            .field("kind", &self.kind)
+            .field("colors", &self.colors)
+            .field("radius", &self.radius)
+            .field("code", &self.code())
+            .finish()
+    }
+}
+
+/// Converts a decimal number to exactly `len` digits in `base`, lowest first.
+///
+/// Returns `None` if `decimal` isn't a number, or if it needs more than `len` digits.
+fn decimal_to_digits(decimal: &str, base: u8, len: usize) -> Option<Vec<u8>> {
+    let decimal = decimal.trim();
+    if decimal.is_empty() {
+        return None;
+    }
+    let mut number = decimal
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    // Long division by `base`, collecting remainders until nothing is left
+    let mut digits = Vec::with_capacity(len);
+    while number.iter().any(|&d| d != 0) {
+        let mut remainder = 0_u32;
+        for d in number.iter_mut() {
+            let value = remainder * 10 + *d as u32;
+            *d = (value / base as u32) as u8;
+            remainder = value % base as u32;
+        }
+        digits.push(remainder as u8);
+        if digits.len() > len {
+            return None;
+        }
+    }
+    digits.resize(len, 0);
+    Some(digits)
+}
+
+/// Converts digits in `base`, lowest first, to a decimal number
+fn digits_to_decimal(digits: &[u8], base: u8) -> String {
+    // Limbs of 9 decimal digits each, lowest first
+    const LIMB: u64 = 1_000_000_000;
+    let mut limbs = alloc::vec![0_u64];
+
+    for &digit in digits.iter().rev() {
+        let mut carry = digit as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * base as u64 + carry;
+            *limb = value % LIMB;
+            carry = value / LIMB;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut text = alloc::format!("{}", limbs.last().unwrap());
+    for limb in limbs.iter().rev().skip(1) {
+        text.push_str(&alloc::format!("{limb:09}"));
+    }
+    text
+}
+
+/// Parses a run of neighbor counts like `236` into a bitmask
+fn parse_counts(digits: &str) -> Option<u16> {
+    let mut mask = 0;
+    for c in digits.chars() {
+        let n = c.to_digit(10).filter(|&n| n <= 8)?;
+        mask |= 1 << n;
+    }
+    Some(mask)
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, mask: u16) -> fmt::Result {
+    for n in 0..=8 {
+        if (mask & (1 << n)) != 0 {
+            write!(f, "{n}")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::conway("B3/S23", LifeRule::CONWAY)]
+    #[case::conway_lowercase("b3/s23", LifeRule::CONWAY)]
+    #[case::conway_swapped("S23/B3", LifeRule::CONWAY)]
+    #[case::conway_sb("23/3", LifeRule::CONWAY)]
+    #[case::highlife("B36/S23", LifeRule::HIGHLIFE)]
+    #[case::highlife_sb("23/36", LifeRule::HIGHLIFE)]
+    #[case::day_and_night("B3678/S34678", LifeRule::DAY_AND_NIGHT)]
+    #[case::seeds("B2/S", LifeRule::SEEDS)]
+    #[case::seeds_sb("/2", LifeRule::SEEDS)]
+    #[case::maze(" B3/S12345 ", LifeRule::MAZE)]
+    #[case::life_without_death("B3/S012345678", LifeRule::LIFE_WITHOUT_DEATH)]
+    fn check_parse(#[case] text: &str, #[case] expected: LifeRule) {
+        assert_eq!(LifeRule::parse(text), Some(expected));
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_slash("B3S23")]
+    #[case::nine("B39/S23")]
+    #[case::mixed("B3/23")]
+    #[case::repeated_letter("B3/B23")]
+    #[case::garbage("Bx/Sy")]
+    fn check_parse_invalid(#[case] text: &str) {
+        assert_eq!(LifeRule::parse(text), None);
+    }
+
+    #[rstest]
+    #[case::conway(LifeRule::CONWAY, "B3/S23")]
+    #[case::seeds(LifeRule::SEEDS, "B2/S")]
+    #[case::b0(LifeRule::from_masks(0b1, 0b1_0000_0000), "B0/S8")]
+    fn check_display_round_trip(#[case] rule: LifeRule, #[case] expected: &str) {
+        assert_eq!(rule.to_string(), expected);
+        assert_eq!(LifeRule::parse(expected), Some(rule));
+    }
+
+    #[rstest]
+    #[case::brians_brain("B2/S/C3", GenerationsRule::BRIANS_BRAIN)]
+    #[case::brians_brain_sbc("/2/3", GenerationsRule::BRIANS_BRAIN)]
+    #[case::brians_brain_lowercase("b2/s/c3", GenerationsRule::BRIANS_BRAIN)]
+    #[case::brians_brain_g("B2/S/G3", GenerationsRule::BRIANS_BRAIN)]
+    #[case::star_wars("B2/S345/C4", GenerationsRule::STAR_WARS)]
+    #[case::star_wars_sbc("345/2/4", GenerationsRule::STAR_WARS)]
+    #[case::star_wars_reordered("C4/S345/B2", GenerationsRule::STAR_WARS)]
+    #[case::life(" B3/S23/C2 ", GenerationsRule::from(LifeRule::CONWAY))]
+    fn check_generations_parse(#[case] text: &str, #[case] expected: GenerationsRule) {
+        assert_eq!(GenerationsRule::parse(text), Some(expected));
+    }
+
+    #[rstest]
+    #[case::life_only("B3/S23")]
+    #[case::one_state("B2/S/C1")]
+    #[case::too_many_states("B2/S/C256")]
+    #[case::missing_count("B2/S/C")]
+    #[case::repeated_count("C3/S/C3")]
+    #[case::two_births("B2/B3/C3")]
+    #[case::mixed("B2/2/C3")]
+    #[case::extra_part("/2/3/4")]
+    fn check_generations_parse_invalid(#[case] text: &str) {
+        assert_eq!(GenerationsRule::parse(text), None);
+    }
+
+    #[test]
+    fn check_generations_next_state() {
+        let rule = GenerationsRule::STAR_WARS;
+
+        // Born on 2, survives on 3-5
+        assert_eq!(rule.next_state(0, 2), 1);
+        assert_eq!(rule.next_state(0, 3), 0);
+        assert_eq!(rule.next_state(1, 4), 1);
+
+        // Otherwise, counts down through the dying states regardless of neighbors
+        assert_eq!(rule.next_state(1, 2), 2);
+        assert_eq!(rule.next_state(2, 4), 3);
+        assert_eq!(rule.next_state(3, 2), 0);
+
+        // With 2 states, this is just the Life-like rule
+        let life = GenerationsRule::from(LifeRule::CONWAY);
+        for n in 0..=8 {
+            for alive in [false, true] {
+                assert_eq!(
+                    life.next_state(alive as u8, n),
+                    LifeRule::CONWAY.next_state(alive, n) as u8
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::rule_30(30, 86, 135, 149)]
+    #[case::rule_110(110, 124, 137, 193)]
+    #[case::rule_90(90, 90, 165, 165)]
+    #[case::rule_184(184, 226, 226, 184)]
+    #[case::rule_0(0, 0, 255, 255)]
+    fn check_elementry_equivalents(
+        #[case] rule: u8,
+        #[case] mirror: u8,
+        #[case] complement: u8,
+        #[case] mirror_complement: u8,
+    ) {
+        let rule = ElementryRule::new(rule);
+        assert_eq!(rule.mirror().number(), mirror);
+        assert_eq!(rule.complement().number(), complement);
+        assert_eq!(rule.mirror_complement().number(), mirror_complement);
+
+        for equivalent in rule.equivalents() {
+            assert_eq!(equivalent.representative(), rule.representative());
+            assert_eq!(equivalent.mirror().mirror(), equivalent);
+            assert_eq!(equivalent.complement().complement(), equivalent);
+        }
+    }
+
+    #[test]
+    fn check_elementry_classes() {
+        let classes: Vec<ElementryRule> = ElementryRule::classes().collect();
+        assert_eq!(classes.len(), 88);
+        assert_eq!(&classes[..4], [0, 1, 2, 3].map(ElementryRule::new));
+
+        // Every rule belongs to exactly one class
+        for number in 0..=255 {
+            let rule = ElementryRule::new(number);
+            let class = classes
+                .iter()
+                .filter(|class| class.equivalents().contains(&rule));
+            assert_eq!(class.count(), 1, "{rule}");
+        }
+    }
+
+    #[test]
+    fn check_elementry_properties() {
+        let find = |property: fn(&ElementryRule) -> bool| -> Vec<u8> {
+            (0..=255)
+                .map(ElementryRule::new)
+                .filter(property)
+                .map(|rule| rule.number())
+                .collect()
+        };
+
+        assert_eq!(
+            find(ElementryRule::is_additive),
+            [0, 60, 90, 102, 150, 170, 204, 240]
+        );
+        assert_eq!(
+            find(ElementryRule::is_number_conserving),
+            [170, 184, 204, 226, 240]
+        );
+        assert_eq!(find(ElementryRule::is_left_permutive).len(), 16);
+
+        assert_eq!(
+            ElementryRule::new(150).additive_terms(),
+            Some([true, true, true])
+        );
+        assert_eq!(
+            ElementryRule::new(60).additive_terms(),
+            Some([true, true, false])
+        );
+        assert_eq!(ElementryRule::new(30).additive_terms(), None);
+
+        let rule_30 = ElementryRule::new(30);
+        assert!(rule_30.is_left_permutive() && !rule_30.is_right_permutive());
+        assert!(rule_30.mirror().is_right_permutive());
+    }
+
+    #[test]
+    fn check_elementry_number_conserving_on_rings() {
+        // Brute force: the population of every ring up to 10 cells stays the same after a step
+        let conserves = |rule: ElementryRule| {
+            (1..=10).all(|len: u32| {
+                (0..1_u32 << len).all(|ring| {
+                    let cell = |i: u32| ring >> (i % len) & 1 != 0;
+                    let next = (0..len)
+                        .filter(|&i| rule.next_state(cell(i + len - 1), cell(i), cell(i + 1)))
+                        .count();
+                    next == ring.count_ones() as usize
+                })
+            })
+        };
+
+        for rule in (0..=255).map(ElementryRule::new) {
+            assert_eq!(rule.is_number_conserving(), conserves(rule), "{rule}");
+        }
+    }
+
+    #[rstest]
+    #[case::number("30", Some(30))]
+    #[case::rule("Rule 30", Some(30))]
+    #[case::lowercase("rule110", Some(110))]
+    #[case::w("W184", Some(184))]
+    #[case::too_big("256", None)]
+    #[case::empty("", None)]
+    #[case::junk("Rule thirty", None)]
+    fn check_elementry_parse(#[case] text: &str, #[case] expected: Option<u8>) {
+        let rule = ElementryRule::parse(text);
+        assert_eq!(rule, expected.map(ElementryRule::new));
+        if let Some(rule) = rule {
+            assert_eq!(ElementryRule::parse(&rule.to_string()), Some(rule));
+        }
+    }
+}
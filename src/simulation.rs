@@ -0,0 +1,259 @@
+use crate::prelude::*;
+use crate::{
+    Automaton, Cycle, CycleDetector, Elementry, Generations, Life, Wireworld, WireworldCell,
+};
+
+/// What happened during a call to [`Simulation::step`] or [`Simulation::step_n`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StepReport {
+    /// The generation the simulation is on after stepping
+    pub generation: u64,
+
+    /// Number of steps taken
+    pub steps: u64,
+
+    /// Number of cells that changed, summed over every step taken
+    pub changed: u64,
+}
+
+impl StepReport {
+    /// Whether nothing changed. For deterministic simulations, this means nothing ever will again.
+    pub fn is_frozen(&self) -> bool {
+        self.changed == 0
+    }
+}
+
+/// Behavior shared by every simulation in this crate, so they can be driven and recorded generically.
+///
+/// Each simulation still has its own inherent `step` method, which may return more detailed information.
+/// Through this trait, each `step` advances the simulation by exactly one generation.
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// # use simulations::{Elementry, Life};
+/// // Runs any simulation until it stops changing, or gives up after `limit` steps
+/// fn run_until_frozen(sim: &mut impl Simulation, limit: u64) -> Option<u64> {
+///     (0..limit)
+///         .find(|_| sim.step().is_frozen())
+///         .map(|_| sim.generation())
+/// }
+///
+/// let mut life: Life = Life::new(8, 8);
+/// life.write_right_glider(1, 1);
+/// assert_eq!(run_until_frozen(&mut life, 100), None);
+///
+/// let mut rule_4: Elementry = Elementry::new(4, 8);
+/// rule_4.set(2, true);
+/// rule_4.set(3, true);
+/// assert_eq!(run_until_frozen(&mut rule_4, 100), Some(2));
+/// ```
+pub trait Simulation {
+    /// The type holding this simulation's state
+    type Grid;
+
+    /// How many generations this simulation has stepped through since it was created or [`reset`](Self::reset)
+    fn generation(&self) -> u64;
+
+    /// Steps the simulation by one generation
+    fn step(&mut self) -> StepReport;
+
+    /// Steps the simulation by `n` generations, returning the combined report
+    fn step_n(&mut self, n: u64) -> StepReport {
+        let mut report = StepReport {
+            generation: self.generation(),
+            ..StepReport::default()
+        };
+        for _ in 0..n {
+            let step = self.step();
+            report.generation = step.generation;
+            report.steps += step.steps;
+            report.changed += step.changed;
+        }
+        report
+    }
+
+    /// Borrow the current state of the simulation
+    fn grid(&self) -> &Self::Grid;
+
+    /// Clears every cell back to its empty state, and the generation counter back to `0`
+    fn reset(&mut self);
+
+    /// Steps the simulation until its cells repeat, giving up after `max_generations` steps.
+    ///
+    /// Cells that repeat somewhere else count too, so spaceships are found along with still lifes and oscillators.
+    /// Only the last `history` generations are remembered. See [`CycleDetector`] for the details.
+    fn find_cycle(&mut self, max_generations: u64, history: usize) -> Option<Cycle>
+    where
+        Self::Grid: Grid,
+    {
+        let mut detector = CycleDetector::new(history);
+        detector.observe(self.generation(), self.grid());
+
+        for _ in 0..max_generations {
+            let report = self.step();
+            if let Some(cycle) = detector.observe(report.generation, self.grid()) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+}
+
+/// Implements [`Simulation`] for a simulation over `Grid<$cell>` by calling its inherent methods of the same names.
+///
+/// Its inherent `step` must return the number of cells changed, and `$grid` must borrow its cells.
+macro_rules! impl_simulation {
+    ($sim:ident, $cell:ty, $grid:ident) => {
+        impl<G: Grid<$cell> + Clone> Simulation for $sim<G> {
+            type Grid = G;
+
+            fn generation(&self) -> u64 {
+                self.generation()
+            }
+
+            fn step(&mut self) -> StepReport {
+                let changed = self.step();
+                StepReport {
+                    generation: self.generation(),
+                    steps: 1,
+                    changed: changed as u64,
+                }
+            }
+
+            fn grid(&self) -> &G {
+                self.$grid()
+            }
+
+            fn reset(&mut self) {
+                self.reset();
+            }
+        }
+    };
+}
+
+impl_simulation!(Life, bool, cells);
+impl_simulation!(Elementry, bool, grid);
+impl_simulation!(Automaton, u8, grid);
+impl_simulation!(Generations, u8, cells);
+impl_simulation!(Wireworld, WireworldCell, cells);
+
+/// Each generation of a `BitFlipper` is a single flip forwards, which always changes exactly one cell.
+impl<G: Grid> Simulation for BitFlipper<G> {
+    type Grid = G;
+
+    fn generation(&self) -> u64 {
+        self.generation()
+    }
+
+    fn step(&mut self) -> StepReport {
+        self.step(1);
+        StepReport {
+            generation: self.generation(),
+            steps: 1,
+            changed: 1,
+        }
+    }
+
+    fn grid(&self) -> &G {
+        self.grid()
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Steps `sim` one generation at a time and `twin` all at once, and checks they agree
+    fn check_step_n_matches_step<S>(mut sim: S, mut twin: S, n: u64)
+    where
+        S: Simulation,
+        S::Grid: PartialEq + core::fmt::Debug,
+    {
+        let mut changed = 0;
+        for i in 0..n {
+            let report = sim.step();
+            assert_eq!(report.generation, i + 1);
+            assert_eq!(report.steps, 1);
+            changed += report.changed;
+        }
+
+        let report = twin.step_n(n);
+        assert_eq!(
+            report,
+            StepReport {
+                generation: n,
+                steps: n,
+                changed,
+            }
+        );
+        assert_eq!(sim.grid(), twin.grid());
+
+        sim.reset();
+        assert_eq!(sim.generation(), 0);
+        assert_eq!(sim.step_n(0), StepReport::default());
+    }
+
+    #[test]
+    fn check_life() {
+        let mut life: Life = Life::new(16, 16);
+        life.write_right_glider(2, 2);
+
+        check_step_n_matches_step(life.clone(), life, 20);
+    }
+
+    #[test]
+    fn check_elementry() {
+        let mut rule_30: Elementry = Elementry::new(30, 64);
+        rule_30.set(32, true);
+
+        check_step_n_matches_step(rule_30.clone(), rule_30, 20);
+    }
+
+    #[test]
+    fn check_automaton() {
+        let rule = crate::AutomatonRule::totalistic("1635", 3, 1).unwrap();
+        let mut sim = Automaton::new(rule, 32);
+        sim.set(16, 1);
+
+        check_step_n_matches_step(sim.clone(), sim, 20);
+    }
+
+    #[test]
+    fn check_generations() {
+        let mut brain = Generations::new(crate::GenerationsRule::BRIANS_BRAIN, 16, 16);
+        brain.set(4, 4, 1);
+        brain.set(5, 4, 1);
+
+        check_step_n_matches_step(brain.clone(), brain, 10);
+    }
+
+    #[test]
+    fn check_wireworld() {
+        let wire = Wireworld::parse("~@######").unwrap();
+        check_step_n_matches_step(wire.clone(), wire, 10);
+    }
+
+    #[test]
+    fn check_bitflipper() {
+        let flipper: BitFlipper = BitFlipper::new(IVec3::new(5, 3, 1), IVec3::new(1, 1, 0));
+        check_step_n_matches_step(flipper.clone(), flipper, 30);
+    }
+
+    #[test]
+    fn check_reset_clears_cells() {
+        let mut life: Life = Life::new(8, 8);
+        life.write_right_glider(1, 1);
+        Simulation::step_n(&mut life, 3);
+
+        Simulation::reset(&mut life);
+        assert_eq!(life.generation(), 0);
+        assert!(life.cells().is_empty());
+    }
+}
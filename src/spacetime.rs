@@ -0,0 +1,300 @@
+use crate::prelude::*;
+
+use alloc::vec;
+
+/// The last few generations of a 1D simulation, stacked into a space-time diagram.
+///
+/// Rows are kept in a circular buffer, so once it is full each new generation replaces the oldest one.
+/// [`to_bitgrid`](Self::to_bitgrid) lays them out oldest first, so row `y` holds generation [`first_generation()`](Self::first_generation)` + y`.
+///
+/// See [`Elementry::space_time`](crate::Elementry::space_time) and [`Elementry::record_history`](crate::Elementry::record_history).
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Elementry, SpaceTime};
+/// let mut rule_90: Elementry = Elementry::new(90, 7);
+/// rule_90.set(3, true);
+///
+/// let diagram = rule_90.space_time(4).to_bitgrid();
+/// let rows: Vec<String> = (0..diagram.height())
+///     .map(|y| (0..diagram.width()).map(|x| if diagram.get(x, y, 0) { 'O' } else { '.' }).collect())
+///     .collect();
+/// assert_eq!(rows, ["...O...", "..O.O..", ".O...O.", "O.O.O.O"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpaceTime {
+    /// One row per generation, in the order they were written. Row `head` is the oldest once the buffer is full.
+    rows: BitGrid,
+
+    /// Index of the oldest row
+    head: usize,
+
+    /// Number of rows holding a generation
+    len: usize,
+
+    /// The generation of the newest row
+    generation: u64,
+}
+
+impl SpaceTime {
+    /// Creates an empty diagram for cells `width` wide, which remembers the last `capacity` generations.
+    ///
+    /// # Panics
+    /// If `capacity` is `0`.
+    #[track_caller]
+    pub fn new(width: usize, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "A SpaceTime must hold at least one generation"
+        );
+        Self {
+            rows: BitGrid::new(width, capacity, 1),
+            head: 0,
+            len: 0,
+            generation: 0,
+        }
+    }
+
+    /// The width of every row
+    pub fn width(&self) -> Index {
+        self.rows.width()
+    }
+
+    /// Number of generations remembered before the oldest are replaced
+    pub fn capacity(&self) -> usize {
+        self.rows.height() as usize
+    }
+
+    /// Number of generations held
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The generation of the oldest row, if there is one
+    pub fn first_generation(&self) -> Option<u64> {
+        (!self.is_empty()).then(|| self.generation + 1 - self.len as u64)
+    }
+
+    /// The generation of the newest row, if there is one
+    pub fn last_generation(&self) -> Option<u64> {
+        (!self.is_empty()).then_some(self.generation)
+    }
+
+    /// Adds the first row of `cells` as `generation`, replacing the oldest row if the diagram is full.
+    ///
+    /// Generations should be pushed one after the other, so that row `y` is always `y` generations after the first.
+    ///
+    /// # Panics
+    /// If `cells` isn't as wide as this diagram.
+    #[track_caller]
+    pub fn push<G: Grid>(&mut self, generation: u64, cells: &G) {
+        assert_eq!(
+            cells.width(),
+            self.width(),
+            "Rows must be as wide as the SpaceTime"
+        );
+
+        let y = ((self.head + self.len) % self.capacity()) as Index;
+        if let Some(cells) = cells.as_bitgrid() {
+            let mut words = vec![0; cells.words_per_row()];
+            cells.read_row(0, 0, &mut words);
+            self.rows.write_row(y, 0, &words);
+        } else {
+            for x in 0..self.width() {
+                self.rows.set(x, y, 0, cells.get(x, 0, 0));
+            }
+        }
+
+        if self.len == self.capacity() {
+            self.head = (self.head + 1) % self.capacity();
+        } else {
+            self.len += 1;
+        }
+        self.generation = generation;
+    }
+
+    /// Checks the cell at `x` in `generation`. Returns `None` if that generation isn't held, or `x` is out of bounds.
+    pub fn get(&self, x: Index, generation: u64) -> Option<bool> {
+        let first = self.first_generation()?;
+        if !(first..=self.generation).contains(&generation) || !(0..self.width()).contains(&x) {
+            return None;
+        }
+        let y = (self.head + (generation - first) as usize) % self.capacity();
+        Some(self.rows.get(x, y as Index, 0))
+    }
+
+    /// Forgets every generation held
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Copies the held generations into a grid [`len()`](Self::len) rows tall, oldest first
+    pub fn to_bitgrid(&self) -> BitGrid {
+        let mut grid = BitGrid::new(self.width() as usize, self.len, 1);
+        let mut words = vec![0; grid.words_per_row()];
+        for y in 0..self.len {
+            let row = (self.head + y) % self.capacity();
+            self.rows.read_row(row as Index, 0, &mut words);
+            grid.write_row(y as Index, 0, &words);
+        }
+        grid
+    }
+}
+
+/// Local typedef to simplify using image's image type. We won't be changing the backing store from a Vec.
+#[cfg(feature = "image")]
+type ImageBuffer<P> = image::ImageBuffer<P, alloc::vec::Vec<<P as image::Pixel>::Subpixel>>;
+
+#[cfg(feature = "image")]
+impl SpaceTime {
+    /// Draws the diagram oldest row first, with every cell as a `scale` by `scale` square.
+    ///
+    /// # Coloring
+    /// Like [`BitGrid::to_image`], **alive** cells are `palette[0]` and **dead** cells are `palette[1]`.
+    ///
+    /// # Panics
+    /// If `scale` is `0`.
+    #[track_caller]
+    pub fn to_image<P>(&self, palette: [P; 2], scale: u32) -> ImageBuffer<P>
+    where
+        P: image::Pixel,
+    {
+        assert!(scale > 0, "Cells must be at least one pixel wide");
+
+        let grid = self.to_bitgrid();
+        ImageBuffer::<P>::from_fn(
+            grid.width() as u32 * scale,
+            grid.height() as u32 * scale,
+            |x: u32, y: u32| {
+                if grid.get((x / scale) as _, (y / scale) as _, 0) {
+                    palette[0]
+                } else {
+                    palette[1]
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Elementry;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    fn rows(grid: &BitGrid) -> alloc::vec::Vec<alloc::string::String> {
+        (0..grid.height())
+            .map(|y| {
+                (0..grid.width())
+                    .map(|x| if grid.get(x, y, 0) { 'O' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_rule_30_diagram() {
+        let mut sim: Elementry = Elementry::new(30, 11);
+        sim.set(5, true);
+
+        let diagram = sim.space_time(5);
+        assert_eq!(
+            rows(&diagram.to_bitgrid()),
+            [
+                ".....O.....",
+                "....OOO....",
+                "...OO..O...",
+                "..OO.OOOO..",
+                ".OO..O...O.",
+            ]
+        );
+        assert_eq!(diagram.first_generation(), Some(0));
+        assert_eq!(diagram.last_generation(), Some(4));
+        assert_eq!(sim.generation(), 4);
+    }
+
+    #[rstest]
+    #[case::partly_full(2, 4)]
+    #[case::exactly_full(3, 4)]
+    #[case::wrapped(10, 4)]
+    fn check_history_keeps_latest(#[case] steps: usize, #[case] capacity: usize) {
+        // Wider than a word, so rows are copied in more than one piece
+        let width = 70;
+        let mut sim: Elementry = Elementry::new(110, width);
+        sim.set(width as Index - 2, true);
+        let full = sim.clone().space_time(steps + 1).to_bitgrid();
+
+        sim.record_history(Some(capacity));
+        for _ in 0..steps {
+            sim.step();
+        }
+
+        let history = sim.history().unwrap();
+        let kept = history.len();
+        assert_eq!(kept, capacity.min(steps + 1));
+        assert_eq!(history.first_generation(), Some((steps + 1 - kept) as u64));
+        assert_eq!(history.last_generation(), Some(steps as u64));
+
+        let expected = rows(&full)[steps + 1 - kept..].to_vec();
+        assert_eq!(rows(&history.to_bitgrid()), expected);
+
+        let last = steps as u64;
+        assert_eq!(
+            history.get(width as Index - 2, 0).is_some(),
+            steps < capacity
+        );
+        assert_eq!(
+            history.get(width as Index - 3, last),
+            Some(full.get(width as Index - 3, steps as Index, 0))
+        );
+        assert_eq!(history.get(width as Index, last), None);
+    }
+
+    #[test]
+    fn check_reset_clears_history() {
+        let mut sim: Elementry = Elementry::new(90, 8);
+        sim.set(4, true);
+        sim.record_history(Some(8));
+        sim.step();
+
+        sim.reset();
+        let history = sim.history().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.first_generation(), Some(0));
+        assert!(history.to_bitgrid().is_empty());
+
+        sim.record_history(None);
+        assert_eq!(sim.history(), None);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_to_image() {
+        use image::Luma;
+
+        let mut sim: Elementry = Elementry::new(90, 3);
+        sim.set(1, true);
+        let image = sim
+            .space_time(2)
+            .to_image([Luma([0_u8]), Luma([255_u8])], 2);
+
+        assert_eq!(image.dimensions(), (6, 4));
+        #[rustfmt::skip]
+        assert_eq!(
+            image.into_raw(),
+            [
+                255, 255, 0, 0, 255, 255,
+                255, 255, 0, 0, 255, 255,
+                0, 0, 255, 255, 0, 0,
+                0, 0, 255, 255, 0, 0,
+            ]
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use crate::prelude::*;
+
+use std::collections::HashSet;
+
+/// An unbounded [`Grid`] that only stores its set cells.
+///
+/// Coordinates are signed and never wrap, so a glider can fly off in any direction forever.
+/// Memory use, [`population`](Self::population), and [`bounding_box`](Self::bounding_box) are all proportional
+/// to the number of set cells, no matter how far apart they are.
+///
+/// As a [`Grid`], its [`extent`](Grid::extent) is the bounding box of its set cells and it ignores any [`Boundary`].
+///
+/// # Example
+/// ```rust
+/// # use simulations::prelude::*;
+/// # use simulations::{Life, SparseGrid};
+/// let mut life: Life<SparseGrid> = Life::new(0, 0);
+/// life.write_right_glider(0, 0);
+///
+/// for _ in 0..4_000 {
+///     life.step();
+/// }
+///
+/// // The glider moved 1000 cells down and to the right, and is still only 5 cells
+/// let cells = life.cells();
+/// assert_eq!(cells.population(), 5);
+/// assert_eq!(
+///     cells.bounding_box(),
+///     Some((IVec3::new(1000, 1000, 0), IVec3::new(1002, 1002, 0)))
+/// );
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SparseGrid {
+    cells: HashSet<IVec3>,
+}
+
+impl core::fmt::Debug for SparseGrid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SparseGrid")
+            .field("population", &self.population())
+            .field("bounding_box", &self.bounding_box())
+            .finish()
+    }
+}
+
+impl SparseGrid {
+    /// Creates a new, empty `SparseGrid`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `SparseGrid` holding the set cells of `grid`, with its `(0, 0, 0)` cell at `origin`
+    pub fn from_bitgrid(grid: &BitGrid, origin: IVec3) -> Self {
+        let mut sparse = Self::new();
+        for z in 0..grid.depth() {
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    if grid.get(x, y, z) {
+                        sparse.cells.insert(origin + IVec3::new(x, y, z));
+                    }
+                }
+            }
+        }
+        sparse
+    }
+
+    /// Copies the region of size `dims` starting at `origin` into a new [`BitGrid`]
+    pub fn to_bitgrid(&self, origin: IVec3, dims: IVec3) -> BitGrid {
+        let mut grid = BitGrid::new(dims.x as usize, dims.y as usize, dims.z as usize);
+        for pos in self.iter() {
+            let local = pos - origin;
+            let in_bounds = (0..dims.x).contains(&local.x)
+                && (0..dims.y).contains(&local.y)
+                && (0..dims.z).contains(&local.z);
+            if in_bounds {
+                grid.set(local.x, local.y, local.z, true);
+            }
+        }
+        grid
+    }
+
+    /// Number of set cells
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest box containing every set cell, as `(min, max)` corners, both inclusive.
+    ///
+    /// Returns `None` if no cells are set.
+    pub fn bounding_box(&self) -> Option<(IVec3, IVec3)> {
+        let mut cells = self.cells.iter();
+        let first = *cells.next()?;
+
+        Some(cells.fold((first, first), |(min, max), &pos| {
+            (min.min_by_component(pos), max.max_by_component(pos))
+        }))
+    }
+
+    /// Iterates over every set cell, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = IVec3> + '_ {
+        self.cells.iter().copied()
+    }
+
+    pub fn get(&self, x: Index, y: Index, z: Index) -> bool {
+        self.cells.contains(&IVec3::new(x, y, z))
+    }
+
+    /// Sets the cell at `(x, y, z)`, returning its previous state
+    pub fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool {
+        let pos = IVec3::new(x, y, z);
+        if elem {
+            !self.cells.insert(pos)
+        } else {
+            self.cells.remove(&pos)
+        }
+    }
+
+    /// Flips the cell at `(x, y, z)`, returning its previous state
+    pub fn flip(&mut self, x: Index, y: Index, z: Index) -> bool {
+        let old = self.get(x, y, z);
+        self.set(x, y, z, !old);
+        old
+    }
+
+    /// Moves every set cell by `offset`
+    pub fn translate(&mut self, offset: IVec3) {
+        if offset != IVec3::zero() {
+            self.cells = self.cells.drain().map(|pos| pos + offset).collect();
+        }
+    }
+
+    /// Unsets every cell
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+impl GridNew for SparseGrid {
+    /// Creates an empty `SparseGrid`. Since it is unbounded, `dims` is ignored.
+    fn new(_dims: IVec3) -> Self {
+        Self::new()
+    }
+}
+
+impl Grid for SparseGrid {
+    /// The size of the bounding box of all set cells, or zero if there are none
+    fn dims(&self) -> IVec3 {
+        let (min, max) = self.extent();
+        max - min
+    }
+
+    fn extent(&self) -> (IVec3, IVec3) {
+        match self.bounding_box() {
+            Some((min, max)) => (min, max + IVec3::one()),
+            None => (IVec3::zero(), IVec3::zero()),
+        }
+    }
+
+    fn is_unbounded(&self) -> bool {
+        true
+    }
+
+    /// An unbounded grid has no edges to resize, so cells are only moved by the anchor's offset.
+    ///
+    /// [`Anchor::Center`] moves cells by half the change between the current bounding box and `new_dims`.
+    fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+        let offset = anchor.offset(Grid::dims(self), new_dims);
+        self.translate(offset);
+    }
+
+    fn get(&self, x: Index, y: Index, z: Index) -> bool {
+        self.get(x, y, z)
+    }
+
+    fn get_with_boundary(&self, x: Index, y: Index, z: Index, _boundary: Boundary) -> bool {
+        self.get(x, y, z)
+    }
+
+    fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool {
+        self.set(x, y, z, elem)
+    }
+
+    fn set_with_boundary(
+        &mut self,
+        x: Index,
+        y: Index,
+        z: Index,
+        elem: bool,
+        _boundary: Boundary,
+    ) -> bool {
+        self.set(x, y, z, elem)
+    }
+
+    fn flip(&mut self, x: Index, y: Index, z: Index) -> bool {
+        self.flip(x, y, z)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    /// Setting every cell of an unbounded grid isn't possible, so `fill(true)` only fills the current extent.
+    fn fill(&mut self, set: bool) {
+        if !set {
+            self.clear();
+            return;
+        }
+
+        let (min, max) = self.extent();
+        for z in min.z..max.z {
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    self.cells.insert(IVec3::new(x, y, z));
+                }
+            }
+        }
+    }
+
+    fn as_sparse(&self) -> Option<&SparseGrid> {
+        Some(self)
+    }
+
+    fn as_sparse_mut(&mut self) -> Option<&mut SparseGrid> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Life;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_get_set() {
+        let mut grid = SparseGrid::new();
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounding_box(), None);
+        assert_eq!(Grid::dims(&grid), IVec3::zero());
+
+        assert!(!grid.set(-5, 10, 0, true));
+        assert!(grid.set(-5, 10, 0, true));
+        assert!(!grid.flip(1_000_000, -3, 2));
+        assert_eq!(grid.population(), 2);
+        assert_eq!(
+            grid.bounding_box(),
+            Some((IVec3::new(-5, -3, 0), IVec3::new(1_000_000, 10, 2)))
+        );
+        assert_eq!(Grid::dims(&grid), IVec3::new(1_000_006, 14, 3));
+
+        // Nothing wraps
+        assert!(!grid.get(-5 + 1_000_006, 10, 0));
+
+        assert!(grid.set(-5, 10, 0, false));
+        assert_eq!(grid.population(), 1);
+        assert_eq!(
+            grid.bounding_box(),
+            Some((IVec3::new(1_000_000, -3, 2), IVec3::new(1_000_000, -3, 2)))
+        );
+    }
+
+    #[test]
+    fn check_bitgrid_round_trip() {
+        let grid = BitGrid::new_with_fn(13, 7, 2, |x, y, z| (x + 2 * y + 5 * z) % 3 == 0);
+        let origin = IVec3::new(-20, 4, 1);
+        let sparse = SparseGrid::from_bitgrid(&grid, origin);
+        assert_eq!(sparse.population(), grid.count_set());
+        assert_eq!(sparse.to_bitgrid(origin, grid.dims()), grid);
+    }
+
+    #[test]
+    fn check_resize_translates() {
+        let mut grid = SparseGrid::new();
+        grid.set(1, 2, 0, true);
+
+        Grid::resize(&mut grid, IVec3::new(100, 100, 100));
+        assert!(grid.get(1, 2, 0));
+
+        grid.resize_with_anchor(IVec3::zero(), Anchor::Offset(IVec3::new(-3, 3, 0)));
+        assert!(grid.get(-2, 5, 0));
+        assert_eq!(grid.population(), 1);
+    }
+
+    #[test]
+    fn check_glider_flies_off() {
+        let mut life: Life<SparseGrid> = Life::new(0, 0);
+        life.write_left_glider(0, 0);
+        let start = life.cells().bounding_box().unwrap();
+
+        for _ in 0..400 {
+            life.step();
+            assert_eq!(life.cells().population(), 5);
+        }
+
+        // Left gliders move down and to the left, one cell every 4 generations
+        let (min, max) = life.cells().bounding_box().unwrap();
+        assert_eq!(min, start.0 + IVec3::new(-100, 100, 0));
+        assert_eq!(max, start.1 + IVec3::new(-100, 100, 0));
+    }
+
+    #[test]
+    fn check_matches_bitgrid_life() {
+        // An R-pentomino in the middle of a torus that's too big for it to reach the edges
+        let r_pentomino = BitGrid::new_with_fn(3, 3, 1, |x, y, _z| {
+            matches!((x, y), (1, 0) | (2, 0) | (0, 1) | (1, 1) | (1, 2))
+        });
+        let origin = IVec3::new(60, 60, 0);
+        let size = 128;
+
+        let mut dense: Life = Life::new(size, size);
+        let mut sparse: Life<SparseGrid> =
+            Life::new_with_cells(SparseGrid::from_bitgrid(&r_pentomino, origin));
+        for (x, y) in sparse.cells().iter().map(|pos| (pos.x, pos.y)) {
+            dense.set(x as i16, y as i16, true);
+        }
+
+        for generation in 0..100 {
+            let dense_count = dense.step();
+            let sparse_count = sparse.step();
+            assert_eq!(
+                dense_count, sparse_count,
+                "Mismatch in generation {generation}"
+            );
+        }
+
+        let dims = IVec3::new(size as Index, size as Index, 1);
+        assert_eq!(
+            &sparse.cells().to_bitgrid(IVec3::zero(), dims),
+            dense.cells()
+        );
+    }
+
+    // Wrapping the grid hides `as_sparse`, so `Life` falls back to its generic path
+    #[derive(Clone)]
+    struct Opaque(SparseGrid);
+
+    impl Grid for Opaque {
+        fn dims(&self) -> IVec3 {
+            Grid::dims(&self.0)
+        }
+        fn extent(&self) -> (IVec3, IVec3) {
+            self.0.extent()
+        }
+        fn is_unbounded(&self) -> bool {
+            true
+        }
+        fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+            self.0.resize_with_anchor(new_dims, anchor);
+        }
+        fn get(&self, x: Index, y: Index, z: Index) -> bool {
+            self.0.get(x, y, z)
+        }
+        fn get_with_boundary(&self, x: Index, y: Index, z: Index, _: Boundary) -> bool {
+            self.0.get(x, y, z)
+        }
+        fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool {
+            self.0.set(x, y, z, elem)
+        }
+    }
+
+    #[test]
+    fn check_generic_step_matches_sparse_step() {
+        let mut fast: Life<SparseGrid> = Life::new(0, 0);
+        fast.write_right_glider(0, 0);
+        fast.write_left_glider(10, 0);
+        fast.write_right_glider(-4, 7);
+        let mut generic = Life::new_with_cells(Opaque(fast.cells().clone()));
+
+        for _ in 0..50 {
+            assert_eq!(fast.step(), generic.step());
+            assert_eq!(fast.cells(), &generic.cells().0);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_b0_panics() {
+        let mut life: Life<SparseGrid> =
+            Life::new_with_rule(crate::LifeRule::parse("B0/S8").unwrap(), 0, 0);
+        life.step();
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_b0_panics_on_generic_step() {
+        let rule = crate::LifeRule::parse("B0/S8").unwrap();
+        let mut life = Life::new_with_cells_and_rule(rule, Opaque(SparseGrid::new()));
+        life.step();
+    }
+}
@@ -0,0 +1,409 @@
+use crate::prelude::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// How the cells of a two-state simulation changed during a single step.
+///
+/// Returned by [`Life::step_with_stats`](crate::Life::step_with_stats) and
+/// [`Elementry::step_with_stats`](crate::Elementry::step_with_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StepStats {
+    /// The generation the simulation is on after this step
+    pub generation: u64,
+
+    /// Number of cells that were **dead** and are now **alive**
+    pub births: u32,
+
+    /// Number of cells that were **alive** and are now **dead**
+    pub deaths: u32,
+
+    /// Number of **alive** cells after this step
+    pub population: u64,
+
+    /// The smallest box holding every cell that changed, with both corners inclusive. `None` if nothing changed.
+    pub changed_bounds: Option<(IVec3, IVec3)>,
+}
+
+impl StepStats {
+    /// Number of cells that changed in either direction, as returned by `step()`
+    pub fn changed(&self) -> u32 {
+        self.births + self.deaths
+    }
+
+    /// Compares two generations of cells, filling in everything but the generation.
+    ///
+    /// Bounded grids must have the same dimensions. Packed words are compared when both grids are [`BitGrid`]s,
+    /// and only stored cells are visited when both are [`SparseGrid`]s.
+    pub(crate) fn between<G: Grid>(old: &G, new: &G) -> Self {
+        if let (Some(old), Some(new)) = (old.as_bitgrid(), new.as_bitgrid()) {
+            return Self::between_words(old, new);
+        }
+
+        #[cfg(feature = "std")]
+        if let (Some(old), Some(new)) = (old.as_sparse(), new.as_sparse()) {
+            let mut stats = Self {
+                population: new.population() as u64,
+                ..Self::default()
+            };
+            for pos in old.iter().filter(|&pos| !new.get(pos.x, pos.y, pos.z)) {
+                stats.deaths += 1;
+                stats.include(pos);
+            }
+            for pos in new.iter().filter(|&pos| !old.get(pos.x, pos.y, pos.z)) {
+                stats.births += 1;
+                stats.include(pos);
+            }
+            return stats;
+        }
+
+        let (old_min, old_max) = old.extent();
+        let (new_min, new_max) = new.extent();
+        let min = old_min.min_by_component(new_min);
+        let max = old_max.max_by_component(new_max);
+
+        let mut stats = Self::default();
+        for z in min.z..max.z {
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    let was_alive = old.get(x, y, z);
+                    let is_alive = new.get(x, y, z);
+                    stats.population += is_alive as u64;
+                    if was_alive != is_alive {
+                        if is_alive {
+                            stats.births += 1;
+                        } else {
+                            stats.deaths += 1;
+                        }
+                        stats.include(IVec3::new(x, y, z));
+                    }
+                }
+            }
+        }
+        stats
+    }
+
+    fn between_words(old: &BitGrid, new: &BitGrid) -> Self {
+        debug_assert_eq!(old.dims(), new.dims());
+
+        let mut stats = Self::default();
+        let mut old_row = vec![0_u64; new.words_per_row()];
+        let mut new_row = vec![0_u64; new.words_per_row()];
+
+        for z in 0..new.depth() {
+            for y in 0..new.height() {
+                old.read_row(y, z, &mut old_row);
+                new.read_row(y, z, &mut new_row);
+
+                for (k, (&was, &is)) in old_row.iter().zip(&new_row).enumerate() {
+                    stats.population += is.count_ones() as u64;
+
+                    let born = is & !was;
+                    let died = was & !is;
+                    stats.births += born.count_ones();
+                    stats.deaths += died.count_ones();
+
+                    // Only the first and last changed cells of each word can widen the bounds
+                    let changed = born | died;
+                    if changed != 0 {
+                        let x = 64 * k as Index;
+                        stats.include(IVec3::new(x + changed.trailing_zeros() as Index, y, z));
+                        stats.include(IVec3::new(x + 63 - changed.leading_zeros() as Index, y, z));
+                    }
+                }
+            }
+        }
+        stats
+    }
+
+    /// Grows [`changed_bounds`](Self::changed_bounds) to hold `pos`
+    fn include(&mut self, pos: IVec3) {
+        self.changed_bounds = Some(match self.changed_bounds {
+            Some((min, max)) => (min.min_by_component(pos), max.max_by_component(pos)),
+            None => (pos, pos),
+        });
+    }
+}
+
+/// An accumulated series of [`StepStats`], one per step, for plotting how a simulation evolves.
+///
+/// Simulations only fill one in while recording. See [`Life::record_stats`](crate::Life::record_stats).
+///
+/// # Example
+/// ```rust
+/// # use simulations::Life;
+/// let mut life: Life = Life::new(16, 16);
+/// life.write_right_glider(1, 1);
+/// life.record_stats(true);
+///
+/// for _ in 0..8 {
+///     life.step();
+/// }
+///
+/// let series = life.time_series().unwrap();
+/// assert_eq!(series.len(), 8);
+/// assert!(series.iter().all(|stats| stats.population == 5));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeSeries {
+    steps: Vec<StepStats>,
+}
+
+impl TimeSeries {
+    /// Creates an empty series
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the stats of another step to the end of the series
+    pub fn push(&mut self, stats: StepStats) {
+        self.steps.push(stats);
+    }
+
+    /// Number of steps in the series
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The most recent step, if there is one
+    pub fn last(&self) -> Option<&StepStats> {
+        self.steps.last()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StepStats> + '_ {
+        self.steps.iter()
+    }
+
+    pub fn as_slice(&self) -> &[StepStats] {
+        &self.steps
+    }
+
+    /// Removes every step from the series
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+}
+
+/// The [`TimeSeries`] of a simulation, filled in one step at a time while recording is turned on.
+///
+/// Shared by every simulation with a `record_stats` method.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StatsRecorder {
+    series: Option<TimeSeries>,
+}
+
+impl StatsRecorder {
+    /// Starts or stops recording. Stopping throws away everything recorded so far.
+    pub(crate) fn set_recording(&mut self, record: bool) {
+        match (record, &self.series) {
+            (true, None) => self.series = Some(TimeSeries::new()),
+            (false, _) => self.series = None,
+            (true, Some(_)) => {}
+        }
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.series.is_some()
+    }
+
+    pub(crate) fn series(&self) -> Option<&TimeSeries> {
+        self.series.as_ref()
+    }
+
+    /// Stops recording, returning everything recorded so far
+    pub(crate) fn take(&mut self) -> Option<TimeSeries> {
+        self.series.take()
+    }
+
+    /// Compares the cells before and after stepping to `generation`, adding the result to the series while recording
+    pub(crate) fn record<G: Grid>(&mut self, generation: u64, old: &G, new: &G) -> StepStats {
+        let stats = StepStats {
+            generation,
+            ..StepStats::between(old, new)
+        };
+        if let Some(series) = &mut self.series {
+            series.push(stats);
+        }
+        stats
+    }
+
+    /// Empties the series, but keeps recording if it was
+    pub(crate) fn clear(&mut self) {
+        if let Some(series) = &mut self.series {
+            series.clear();
+        }
+    }
+}
+
+/// `std`-only functions
+#[cfg(feature = "std")]
+impl TimeSeries {
+    /// Column names written by [`write_csv`](Self::write_csv)
+    pub const CSV_HEADER: &'static str =
+        "generation,births,deaths,population,min_x,min_y,min_z,max_x,max_y,max_z";
+
+    /// Writes the series as CSV with a header row, then one row per step.
+    ///
+    /// The bounds columns are left empty for steps where nothing changed.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{StepStats, TimeSeries};
+    /// # use simulations::prelude::IVec3;
+    /// let mut series = TimeSeries::new();
+    /// series.push(StepStats {
+    ///     generation: 1,
+    ///     births: 2,
+    ///     deaths: 1,
+    ///     population: 4,
+    ///     changed_bounds: Some((IVec3::new(0, 1, 0), IVec3::new(2, 3, 0))),
+    /// });
+    /// series.push(StepStats { generation: 2, population: 4, ..StepStats::default() });
+    ///
+    /// let mut csv = Vec::new();
+    /// series.write_csv(&mut csv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     "generation,births,deaths,population,min_x,min_y,min_z,max_x,max_y,max_z\n\
+    ///      1,2,1,4,0,1,0,2,3,0\n\
+    ///      2,0,0,4,,,,,,\n"
+    /// );
+    /// ```
+    pub fn write_csv(&self, mut w: impl std::io::Write) -> std::io::Result<()> {
+        writeln!(w, "{}", Self::CSV_HEADER)?;
+        for stats in &self.steps {
+            write!(
+                w,
+                "{},{},{},{},",
+                stats.generation, stats.births, stats.deaths, stats.population
+            )?;
+            match stats.changed_bounds {
+                Some((min, max)) => writeln!(
+                    w,
+                    "{},{},{},{},{},{}",
+                    min.x, min.y, min.z, max.x, max.y, max.z
+                )?,
+                None => writeln!(w, ",,,,,")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Extend<StepStats> for TimeSeries {
+    fn extend<I: IntoIterator<Item = StepStats>>(&mut self, iter: I) {
+        self.steps.extend(iter);
+    }
+}
+
+impl FromIterator<StepStats> for TimeSeries {
+    fn from_iter<I: IntoIterator<Item = StepStats>>(iter: I) -> Self {
+        Self {
+            steps: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+    use rstest::*;
+
+    /// Hides the grid type, so stats are computed one cell at a time
+    #[derive(Clone, PartialEq, Debug)]
+    struct Opaque(BitGrid);
+
+    impl Grid for Opaque {
+        fn dims(&self) -> IVec3 {
+            self.0.dims()
+        }
+
+        fn resize_with_anchor(&mut self, new_dims: IVec3, anchor: Anchor) {
+            self.0.resize_with_anchor(new_dims, anchor)
+        }
+
+        fn get(&self, x: Index, y: Index, z: Index) -> bool {
+            self.0.get(x, y, z)
+        }
+
+        fn set(&mut self, x: Index, y: Index, z: Index, elem: bool) -> bool {
+            self.0.set(x, y, z, elem)
+        }
+    }
+
+    #[rstest]
+    #[case::narrow(7, 5, 1)]
+    #[case::word(64, 3, 2)]
+    #[case::wide(150, 4, 1)]
+    fn check_words_match_cells(#[case] width: usize, #[case] height: usize, #[case] depth: usize) {
+        let mut rng = SmallRng::seed_from_u64(12);
+        let old = BitGrid::new_with_fn(width, height, depth, |_, _, _| rng.random());
+        let new = BitGrid::new_with_fn(width, height, depth, |_, _, _| rng.random_bool(0.1));
+
+        let words = StepStats::between(&old, &new);
+        let cells = StepStats::between(&Opaque(old.clone()), &Opaque(new.clone()));
+
+        assert_eq!(words, cells);
+        assert_eq!(words.population, new.count_set() as u64);
+        assert_eq!(words.changed(), old.diff_with(&new).count_set() as u32);
+    }
+
+    #[test]
+    fn check_bounds_of_single_change() {
+        let old = BitGrid::new(100, 3, 1);
+        let mut new = old.clone();
+        new.set(70, 2, 0, true);
+
+        let stats = StepStats::between(&old, &new);
+        assert_eq!(
+            stats,
+            StepStats {
+                generation: 0,
+                births: 1,
+                deaths: 0,
+                population: 1,
+                changed_bounds: Some((IVec3::new(70, 2, 0), IVec3::new(70, 2, 0))),
+            }
+        );
+
+        assert_eq!(StepStats::between(&new, &new).changed_bounds, None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn check_sparse_matches_cells() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let old = BitGrid::new_with_fn(20, 20, 1, |_, _, _| rng.random());
+        let new = BitGrid::new_with_fn(20, 20, 1, |_, _, _| rng.random());
+
+        let offset = IVec3::new(-5, 7, 0);
+        let sparse = StepStats::between(
+            &SparseGrid::from_bitgrid(&old, offset),
+            &SparseGrid::from_bitgrid(&new, offset),
+        );
+        let mut expected = StepStats::between(&old, &new);
+        expected.changed_bounds = expected
+            .changed_bounds
+            .map(|(min, max)| (min + offset, max + offset));
+
+        assert_eq!(sparse, expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn check_csv_of_empty_series() {
+        let mut csv = Vec::new();
+        TimeSeries::new().write_csv(&mut csv).unwrap();
+        assert_eq!(
+            csv,
+            alloc::format!("{}\n", TimeSeries::CSV_HEADER).into_bytes()
+        );
+    }
+}
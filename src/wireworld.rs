@@ -0,0 +1,502 @@
+use crate::prelude::*;
+
+use alloc::string::String;
+
+/// The state of a single cell in [`Wireworld`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WireworldCell {
+    /// Background that never changes
+    #[default]
+    Empty,
+
+    /// The front of an electron. Becomes a [`Tail`](Self::Tail).
+    Head,
+
+    /// The back of an electron. Becomes a [`Conductor`](Self::Conductor).
+    Tail,
+
+    /// Wire that electrons travel along. Becomes a [`Head`](Self::Head) next to 1 or 2 heads.
+    Conductor,
+}
+
+impl WireworldCell {
+    /// The state of this cell in the next generation, given its number of neighboring [`Head`](Self::Head)s
+    pub fn next_state(self, heads: u8) -> Self {
+        match self {
+            Self::Empty => Self::Empty,
+            Self::Head => Self::Tail,
+            Self::Tail => Self::Conductor,
+            Self::Conductor if heads == 1 || heads == 2 => Self::Head,
+            Self::Conductor => Self::Conductor,
+        }
+    }
+
+    /// Character for this cell in the text format. See [`Wireworld::parse`].
+    fn to_char(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Head => '@',
+            Self::Tail => '~',
+            Self::Conductor => '#',
+        }
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' | ' ' => Some(Self::Empty),
+            '@' | 'H' => Some(Self::Head),
+            '~' | 't' | 'T' => Some(Self::Tail),
+            '#' | 'C' => Some(Self::Conductor),
+            _ => None,
+        }
+    }
+}
+
+/// Cells are numbered like Golly's `WireWorld` rule: empty, head, tail, then conductor.
+///
+/// [`Boundary::Alive`] surrounds a grid with electron heads.
+impl Cell for WireworldCell {
+    const BITS: u8 = 2;
+    const DEAD: Self = Self::Empty;
+    const ALIVE: Self = Self::Head;
+
+    fn to_bits(self) -> u8 {
+        self as u8
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0 => Self::Empty,
+            1 => Self::Head,
+            2 => Self::Tail,
+            _ => Self::Conductor,
+        }
+    }
+}
+
+/// Brian Silverman's Wireworld, a cellular automaton for simulating digital logic.
+///
+/// Electrons travel along wires ([`Conductor`](WireworldCell::Conductor)s) as a [`Head`](WireworldCell::Head)
+/// followed by a [`Tail`](WireworldCell::Tail). A conductor becomes a head when exactly 1 or 2 of its 8 neighbors are heads,
+/// so electrons flow down wires, split at junctions, and can be combined into diodes and logic gates.
+///
+/// # Example
+/// ```rust
+/// # use simulations::{Wireworld, WireworldCell};
+/// # use simulations::Boundary;
+/// let mut wire = Wireworld::parse("~@###").unwrap();
+/// wire.set_boundary(Boundary::Dead);
+///
+/// wire.step();
+/// assert_eq!(wire.to_text(), "#~@##\n");
+/// assert_eq!(wire.get(2, 0), WireworldCell::Head);
+/// ```
+#[derive(Clone)]
+pub struct Wireworld<G: Grid<WireworldCell> = PackedGrid<WireworldCell>> {
+    /// Current state of the simulation
+    cells: G,
+
+    /// Scratch copy of cells used when stepping the simulation
+    scratch: G,
+
+    /// How cells beyond the edges of `cells` are treated
+    boundary: Boundary,
+
+    /// Number of steps taken since creation or the last reset
+    generation: u64,
+}
+
+/// Basic Usage
+impl<G: GridNew<WireworldCell> + Clone> Wireworld<G> {
+    /// Creates a new `Wireworld` simulation with the given dimensions where all cells are initially **empty**.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::new_with_cells(G::new(IVec3::new(width as Index, height as Index, 1)))
+    }
+}
+
+impl<G: Grid<WireworldCell> + Clone> Wireworld<G> {
+    /// Creates a new `Wireworld` simulation with the given cells
+    ///
+    /// The simulation uses the [`Boundary`] of `cells`. See [`set_boundary`](Self::set_boundary) to override it.
+    pub fn new_with_cells(cells: G) -> Self {
+        let scratch = cells.clone();
+        let boundary = cells.boundary();
+        Self {
+            cells,
+            scratch,
+            boundary,
+            generation: 0,
+        }
+    }
+}
+
+impl<G: Grid<WireworldCell>> Wireworld<G> {
+    /// The width of the simulation
+    pub fn width(&self) -> i16 {
+        self.cells.width() as i16
+    }
+
+    /// The height of the simulation
+    pub fn height(&self) -> i16 {
+        self.cells.height() as i16
+    }
+
+    /// How many generations have been stepped through since this simulation was created or [`reset`](Self::reset)
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// How cells beyond the edges of the simulation are treated
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Changes how cells beyond the edges of the simulation are treated.
+    ///
+    /// This overrides the [`Boundary`] of the underlying grid for this simulation only.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    /// Checks the cell at `(x, y)`.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    #[track_caller]
+    pub fn get(&self, x: i16, y: i16) -> WireworldCell {
+        self.cells
+            .get_with_boundary(x as Index, y as Index, 0, self.boundary)
+    }
+
+    /// Sets the cell at `(x, y)`, returning its previous state.
+    ///
+    /// Out of bounds access is resolved with [`boundary()`](Self::boundary).
+    /// Writes to cells outside of a bounded simulation are ignored.
+    #[track_caller]
+    pub fn set(&mut self, x: i16, y: i16, cell: WireworldCell) -> WireworldCell {
+        self.cells
+            .set_with_boundary(x as Index, y as Index, 0, cell, self.boundary)
+    }
+
+    /// Number of cells in the state `cell`
+    pub fn count(&self, cell: WireworldCell) -> usize {
+        let (min, max) = self.cells.extent();
+        (min.y..max.y)
+            .flat_map(|y| (min.x..max.x).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.cells.get(x, y, 0) == cell)
+            .count()
+    }
+
+    pub fn cells(&self) -> &G {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut G {
+        &mut self.cells
+    }
+
+    pub fn into_cells(self) -> G {
+        self.cells
+    }
+
+    /// Steps the simulation once, returning the number of cells updated
+    ///
+    /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
+    pub fn step(&mut self) -> u32 {
+        let mut count = 0;
+
+        let (min, max) = self.cells.extent();
+        let head = |x: Index, y: Index| {
+            (self.cells.get_with_boundary(x, y, 0, self.boundary) == WireworldCell::Head) as u8
+        };
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let was = self.cells.get(x, y, 0);
+
+                // Only conductors care about their neighbors
+                let is = if was == WireworldCell::Conductor {
+                    let mut heads = 0;
+
+                    heads += head(x - 1, y - 1);
+                    heads += head(x - 1, y + 0);
+                    heads += head(x - 1, y + 1);
+
+                    heads += head(x + 0, y - 1);
+                    heads += head(x + 0, y + 1);
+
+                    heads += head(x + 1, y - 1);
+                    heads += head(x + 1, y + 0);
+                    heads += head(x + 1, y + 1);
+
+                    was.next_state(heads)
+                } else {
+                    was.next_state(0)
+                };
+
+                self.scratch.set(x, y, 0, is);
+
+                if was != is {
+                    count += 1;
+                }
+            }
+        }
+
+        core::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
+
+        count
+    }
+
+    /// Marks all cells as **empty**
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Marks all cells as **empty** and sets the [generation](Self::generation) back to `0`
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generation = 0;
+    }
+
+    /// Writes the cells in the text format read by [`parse`](Wireworld::parse), one line per row.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for y in 0..self.cells.height() {
+            for x in 0..self.cells.width() {
+                text.push(self.cells.get(x, y, 0).to_char());
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Loading and saving
+impl Wireworld<PackedGrid<WireworldCell>> {
+    /// Parses the plain text format, one line per row.
+    ///
+    /// | Cell        | Characters      |
+    /// |-------------|-----------------|
+    /// | Empty       | `.` or space    |
+    /// | Head        | `@` or `H`      |
+    /// | Tail        | `~`, `t`, or `T`|
+    /// | Conductor   | `#` or `C`      |
+    ///
+    /// Lines may have different lengths: the simulation is as wide as the longest line, and short lines are padded with empty cells.
+    /// Returns `None` if any other character is used.
+    pub fn parse(text: &str) -> Option<Self> {
+        let lines: alloc::vec::Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+        let mut wireworld = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                wireworld
+                    .cells
+                    .set(x as Index, y as Index, 0, WireworldCell::from_char(c)?);
+            }
+        }
+
+        Some(wireworld)
+    }
+
+    /// Creates a new simulation from a pattern in the RLE format, as saved by Golly. See [`Rle`](crate::Rle) for details.
+    ///
+    /// States are numbered like [`WireworldCell`]: `.` is empty, `A` is a head, `B` is a tail, and `C` is a conductor.
+    /// Returns `None` if the pattern can't be parsed, has another state, or has a rule other than `WireWorld`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use simulations::{Wireworld, WireworldCell};
+    /// let wire = Wireworld::from_rle("x = 4, y = 1, rule = WireWorld\nBA2C!").unwrap();
+    /// assert_eq!(wire.get(0, 0), WireworldCell::Tail);
+    /// assert_eq!(wire.count(WireworldCell::Conductor), 2);
+    /// ```
+    pub fn from_rle(text: &str) -> Option<Self> {
+        let (header, grid) = crate::rle::parse_states(text)?;
+        if let Some(rule) = &header.rule {
+            if !rule.eq_ignore_ascii_case("wireworld") {
+                return None;
+            }
+        }
+
+        let mut wireworld = Self::new(header.width, header.height);
+        for (pos, state) in grid.iter() {
+            if state > WireworldCell::Conductor as u8 {
+                return None;
+            }
+            wireworld
+                .cells
+                .set(pos.x, pos.y, 0, WireworldCell::from_bits(state));
+        }
+
+        Some(wireworld)
+    }
+
+    /// Writes the current cells in the RLE format, with the rule set to `WireWorld`
+    pub fn to_rle(&self) -> String {
+        let states = PackedGrid::new_with_fn(
+            self.cells.width() as usize,
+            self.cells.height() as usize,
+            1,
+            |x, y, _| self.cells.get(x, y, 0).to_bits(),
+        );
+        crate::rle::write_states(&states, Some("WireWorld".into()))
+    }
+}
+
+/// Local typedef to simplify using image's image type. We won't be changing the backing store from a Vec.
+#[cfg(feature = "image")]
+type ImageBuffer<P> = image::ImageBuffer<P, alloc::vec::Vec<<P as image::Pixel>::Subpixel>>;
+
+#[cfg(feature = "image")]
+impl<G: Grid<WireworldCell>> Wireworld<G> {
+    /// The usual Wireworld colors, indexed by [`WireworldCell`]: black, blue, red, and yellow
+    pub const PALETTE: [image::Rgb<u8>; 4] = [
+        image::Rgb([0x00, 0x00, 0x00]),
+        image::Rgb([0x00, 0x80, 0xff]),
+        image::Rgb([0xff, 0x40, 0x00]),
+        image::Rgb([0xff, 0xcc, 0x00]),
+    ];
+
+    /// Convert the cells into an [`image::ImageBuffer`](image::ImageBuffer) with the usual colors.
+    /// See [`PALETTE`](Self::PALETTE).
+    pub fn to_image(&self) -> ImageBuffer<image::Rgb<u8>> {
+        self.to_image_with_palette(Self::PALETTE)
+    }
+
+    /// Convert the cells into an [`image::ImageBuffer`](image::ImageBuffer).
+    ///
+    /// # Coloring
+    /// Each cell is colored with `palette[cell as usize]`, so the order is empty, head, tail, then conductor.
+    pub fn to_image_with_palette<P>(&self, palette: [P; 4]) -> ImageBuffer<P>
+    where
+        P: image::Pixel,
+    {
+        ImageBuffer::<P>::from_fn(
+            self.cells.width() as u32,
+            self.cells.height() as u32,
+            |x: u32, y: u32| palette[self.cells.get(x as _, y as _, 0) as usize],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_electron_travels_along_wire() {
+        let mut wire = Wireworld::parse("~@####").unwrap();
+        wire.set_boundary(Boundary::Dead);
+
+        let mut frames = alloc::vec![wire.to_text()];
+        for _ in 0..4 {
+            assert_eq!(wire.step(), 3);
+            frames.push(wire.to_text());
+        }
+        assert_eq!(
+            frames,
+            ["~@####\n", "#~@###\n", "##~@##\n", "###~@#\n", "####~@\n"]
+        );
+
+        // The electron falls off the end of the wire
+        assert_eq!(wire.step(), 2);
+        assert_eq!(wire.to_text(), "#####~\n");
+    }
+
+    #[test]
+    fn check_diode() {
+        // A diode lets electrons through in one direction only
+        let forward = indoc! {"
+            .....###.....
+            ~@#####.#####
+            .....#.......
+        "};
+        let backward = indoc! {"
+            .....###.....
+            #######.###@~
+            .....#.......
+        "};
+
+        let mut forward = Wireworld::parse(forward).unwrap();
+        forward.set_boundary(Boundary::Dead);
+        let mut backward = Wireworld::parse(backward).unwrap();
+        backward.set_boundary(Boundary::Dead);
+
+        let mut passed_forward = false;
+        let mut passed_backward = false;
+        for _ in 0..14 {
+            forward.step();
+            backward.step();
+            passed_forward |= forward.get(12, 1) == WireworldCell::Head;
+            passed_backward |= backward.get(0, 1) == WireworldCell::Head;
+        }
+
+        assert!(passed_forward);
+        assert!(!passed_backward);
+    }
+
+    #[test]
+    fn check_too_many_heads_blocks() {
+        // A conductor next to 3 heads stays a conductor
+        let mut wire = Wireworld::parse("@@@\n.#.").unwrap();
+        wire.set_boundary(Boundary::Dead);
+        wire.step();
+
+        assert_eq!(wire.to_text(), "~~~\n.#.\n");
+        assert_eq!(wire.count(WireworldCell::Tail), 3);
+    }
+
+    #[test]
+    fn check_text_variants() {
+        let a = Wireworld::parse("tH CC\n# ~@").unwrap();
+        let b = Wireworld::parse("~@.##\n#.~@").unwrap();
+
+        assert_eq!(a.to_text(), b.to_text());
+        assert_eq!(a.to_text(), "~@.##\n#.~@.\n");
+        assert!(Wireworld::parse("#x#").is_none());
+    }
+
+    #[test]
+    fn check_rle_round_trip() {
+        let text = "x = 5, y = 2, rule = WireWorld\nBA3C$C3.C!\n";
+        let wire = Wireworld::from_rle(text).unwrap();
+
+        assert_eq!(wire.to_text(), "~@###\n#...#\n");
+        assert_eq!(wire.to_rle(), text);
+
+        assert!(Wireworld::from_rle("x = 1, y = 1, rule = B3/S23\nA!").is_none());
+        assert!(Wireworld::from_rle("x = 1, y = 1\nD!").is_none());
+    }
+
+    #[test]
+    fn check_wider_than_i16() {
+        let text = "x = 40000, y = 1, rule = WireWorld\n39998.BA!\n";
+        let wire = Wireworld::from_rle(text).unwrap();
+        assert_eq!(wire.cells().get(39_998, 0, 0), WireworldCell::Tail);
+        assert_eq!(wire.cells().get(39_999, 0, 0), WireworldCell::Head);
+        assert_eq!(wire.to_rle(), text);
+
+        let line: String = core::iter::repeat_n('.', 39_999).chain(['@']).collect();
+        let wire = Wireworld::parse(&line).unwrap();
+        assert_eq!(wire.cells().get(39_999, 0, 0), WireworldCell::Head);
+        assert_eq!(wire.count(WireworldCell::Head), 1);
+        assert_eq!(wire.to_text(), line + "\n");
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn check_to_image() {
+        let wire = Wireworld::parse(".@~#").unwrap();
+        let image = wire.to_image();
+
+        assert_eq!(
+            image.pixels().copied().collect::<alloc::vec::Vec<_>>(),
+            Wireworld::<PackedGrid<WireworldCell>>::PALETTE
+        );
+    }
+}